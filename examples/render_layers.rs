@@ -10,7 +10,7 @@ fn main() {
     App::new()
         .add_plugins((
             DefaultPlugins,
-            ChildWindowPlugin,
+            ChildWindowPlugin::default(),
         ))
         .add_systems(Startup, (
             spawn_child_window,
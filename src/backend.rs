@@ -0,0 +1,313 @@
+use crate::drag::DragHitTest;
+use crate::interaction::Interaction;
+use crate::platform_impl::NativeBackend;
+use crate::resize::ResizeBorderHitTest;
+use crate::settings::SharedSettings;
+use crate::tear_off::DragOvershoot;
+use crate::util::log_debug;
+use crate::{ChildWindowLock, ChildWindowNativeResources, ChildWindowSettings, ChildWindowSystems, ParentWindow, UnInitializeWindow};
+use bevy::app::{App, Plugin};
+use bevy::ecs::schedule::InternedScheduleLabel;
+use bevy::log::warn;
+use bevy::math::Rect;
+use bevy::prelude::{any_with_component, Commands, Deref, Entity, IntoScheduleConfigs, NonSend, Query, RemovedComponents, Res, Resource, With};
+use bevy::window::Window;
+use bevy::winit::WinitWindows;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+pub(crate) struct ChildWindowBackendPlugin {
+    /// If `None`, [`NativeBackend`] is used.
+    pub(crate) backend: Option<Arc<dyn ChildWindowBackend>>,
+    pub(crate) settings: ChildWindowSettings,
+    pub(crate) attach_schedule: InternedScheduleLabel,
+}
+
+impl Plugin for ChildWindowBackendPlugin {
+    fn build(&self, app: &mut App) {
+        let shared = Arc::new(SharedState::new(self.settings));
+        let backend = self
+            .backend
+            .clone()
+            .unwrap_or_else(|| Arc::new(NativeBackend::new(shared.clone())));
+        app
+            .insert_resource(ActiveBackend { backend, shared })
+            .add_systems(self.attach_schedule, (
+                detach_child_windows,
+                attach_child_windows.run_if(any_with_component::<UnInitializeWindow>),
+            )
+                .chain()
+                .in_set(ChildWindowSystems::Attach));
+    }
+}
+
+/// The native implementation of child windows.
+///
+/// The built-in backend for Windows and macOS is used by default.
+/// An alternative backend, such as a mock for tests or an implementation for other platforms,
+/// can be registered with [`ChildWindowPlugin::with_backend`](crate::ChildWindowPlugin::with_backend).
+/// The platform-specific features of the built-in backends, such as the drag and resize handling of the native windows, are not available then.
+///
+/// All methods are called on the main thread.
+/// All rects are the client area of the child window, excluding its title bar and borders,
+/// in logical pixels relative to the top-left corner of the parent window's client area.
+/// So the size of a rect is the same as [`Window::size`], and a rect returned by [`ChildWindowBackend::query_rect`]
+/// is kept as it is when passed to [`ChildWindowBackend::set_rect`].
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::winit::WinitWindows;
+/// use bevy_child_window::prelude::*;
+///
+/// /// Keeps child windows as top-level windows, e.g. for tests.
+/// struct MockBackend;
+///
+/// impl ChildWindowBackend for MockBackend {
+///     fn attach(&self, _: &WinitWindows, _: Entity, _: &Window, _: Entity) -> bool {
+///         true
+///     }
+///
+///     fn detach(&self, _: &WinitWindows, _: Entity) {}
+///
+///     fn set_rect(&self, _: &WinitWindows, _: Entity, _: Entity, _: Rect) {}
+///
+///     fn raise(&self, _: &WinitWindows, _: Entity) {}
+///
+///     fn focus(&self, _: &WinitWindows, _: Entity) {}
+///
+///     fn query_rect(&self, _: &WinitWindows, _: Entity, _: Entity) -> Option<Rect> {
+///         None
+///     }
+/// }
+///
+/// fn main() {
+///     App::new()
+///         .add_plugins((
+///             DefaultPlugins,
+///             ChildWindowPlugin::default().with_backend(MockBackend),
+///         ))
+///         .run();
+/// }
+/// ```
+pub trait ChildWindowBackend: Send + Sync + 'static {
+    /// Attaches the native window of `entity` to the native window of `parent`.
+    ///
+    /// Returns false if the native windows are not ready yet, in which case it is retried in the next frame
+    /// according to [`AttachRetryPolicy`](crate::AttachRetryPolicy).
+    fn attach(&self, winit_windows: &WinitWindows, entity: Entity, window: &Window, parent: Entity) -> bool;
+
+    /// Releases the native resources of the child window, and detaches it from its parent window if it is still alive.
+    ///
+    /// This is also called for windows that have never been attached, or that have already been destroyed.
+    fn detach(&self, winit_windows: &WinitWindows, entity: Entity);
+
+    /// Moves and resizes the child window so that its client area becomes `rect`.
+    ///
+    /// The backend adds the decorations of the child window around `rect`.
+    fn set_rect(&self, winit_windows: &WinitWindows, entity: Entity, parent: Entity, rect: Rect);
+
+    /// Brings the child window to the front of its sibling child windows.
+    fn raise(&self, winit_windows: &WinitWindows, entity: Entity);
+
+    /// Raises the child window and gives it the keyboard focus.
+    fn focus(&self, winit_windows: &WinitWindows, entity: Entity);
+
+    /// Returns the current client area of the child window.
+    ///
+    /// This is used to keep the position of the child window when only its size is changed with
+    /// [`ChildWindowEntityCommands::resize`](crate::ChildWindowEntityCommands::resize).
+    fn query_rect(&self, winit_windows: &WinitWindows, entity: Entity, parent: Entity) -> Option<Rect>;
+
+    /// Starts moving the child window with the current mouse drag.
+    ///
+    /// By default, the native move of `winit` is started.
+    fn start_drag(&self, winit_windows: &WinitWindows, entity: Entity, _parent: Entity) {
+        let Some(window) = winit_windows.get_window(entity) else {
+            return;
+        };
+        if let Err(e) = window.drag_window() {
+            warn!("{e}");
+        }
+    }
+
+    /// Applies the decoration fields of `window` after `winit` changed them.
+    ///
+    /// Does nothing by default.
+    fn apply_decorations(&self, _winit_windows: &WinitWindows, _entity: Entity, _window: &Window) {}
+
+    /// Returns the number of native resources held for child windows, which is reported as [`ChildWindowNativeResources`].
+    ///
+    /// Returns zero by default.
+    fn native_resources(&self) -> ChildWindowNativeResources {
+        ChildWindowNativeResources::default()
+    }
+}
+
+/// The backend registered through [`ChildWindowPlugin`](crate::ChildWindowPlugin).
+#[derive(Resource, Deref)]
+pub(crate) struct ActiveBackend {
+    #[deref]
+    backend: Arc<dyn ChildWindowBackend>,
+    /// The state shared with the native event handlers of the built-in backend.
+    pub(crate) shared: Arc<SharedState>,
+}
+
+/// The state of child windows shared between the ECS and the native event handlers, which run outside the ECS.
+///
+/// Each app owns its own state, which the built-in backend hands to the handlers of its child windows,
+/// so the entities of several apps in the same process never mix.
+#[derive(Default)]
+pub(crate) struct SharedState {
+    pub(crate) settings: Mutex<SharedSettings>,
+    pub(crate) locks: Mutex<BTreeMap<Entity, ChildWindowLock>>,
+    pub(crate) drag_hit_tests: Mutex<BTreeMap<Entity, DragHitTest>>,
+    pub(crate) resize_borders: Mutex<BTreeMap<Entity, ResizeBorderHitTest>>,
+    pub(crate) interactions: Mutex<Vec<Interaction>>,
+    pub(crate) drag_overshoots: Mutex<Vec<DragOvershoot>>,
+}
+
+impl SharedState {
+    pub(crate) fn new(settings: ChildWindowSettings) -> Self {
+        Self {
+            settings: Mutex::new(SharedSettings::new(settings)),
+            ..Default::default()
+        }
+    }
+}
+
+fn detach_child_windows(
+    mut removed: RemovedComponents<ParentWindow>,
+    backend: Res<ActiveBackend>,
+    winit_windows: NonSend<WinitWindows>,
+) {
+    for entity in removed.read() {
+        backend.detach(&winit_windows, entity);
+    }
+}
+
+fn attach_child_windows(
+    mut commands: Commands,
+    backend: Res<ActiveBackend>,
+    settings: Res<ChildWindowSettings>,
+    winit_windows: NonSend<WinitWindows>,
+    windows: Query<(Entity, &Window, &ParentWindow), With<UnInitializeWindow>>,
+) {
+    for (entity, window, ParentWindow(parent)) in windows.iter() {
+        if backend.attach(&winit_windows, entity, window, *parent) {
+            commands.entity(entity).remove::<UnInitializeWindow>();
+            log_debug!(settings.log_level, "Attached the child window {entity} to {parent}");
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{ChildWindowCommands, ChildWindowEntityCommands, ChildWindowPlugin};
+    use bevy::app::TaskPoolPlugin;
+    use bevy::input::InputPlugin;
+    use bevy::math::Vec2;
+    use bevy::platform::collections::HashMap;
+    use bevy::window::{ExitCondition, WindowPlugin};
+
+    /// Records the calls of the plugin and keeps the rects of the child windows in memory.
+    #[derive(Default)]
+    pub(crate) struct MockBackend {
+        pub(crate) rects: Mutex<HashMap<Entity, Rect>>,
+        pub(crate) raised: Mutex<Vec<Entity>>,
+    }
+
+    impl ChildWindowBackend for MockBackend {
+        fn attach(&self, _: &WinitWindows, entity: Entity, window: &Window, _: Entity) -> bool {
+            self.rects.lock().unwrap().insert(entity, Rect::from_corners(Vec2::ZERO, window.size()));
+            true
+        }
+
+        fn detach(&self, _: &WinitWindows, entity: Entity) {
+            self.rects.lock().unwrap().remove(&entity);
+        }
+
+        fn set_rect(&self, _: &WinitWindows, entity: Entity, _: Entity, rect: Rect) {
+            if let Some(r) = self.rects.lock().unwrap().get_mut(&entity) {
+                *r = rect;
+            }
+        }
+
+        fn raise(&self, _: &WinitWindows, entity: Entity) {
+            self.raised.lock().unwrap().push(entity);
+        }
+
+        fn focus(&self, winit_windows: &WinitWindows, entity: Entity) {
+            self.raise(winit_windows, entity);
+        }
+
+        fn query_rect(&self, _: &WinitWindows, entity: Entity, _: Entity) -> Option<Rect> {
+            self.rects.lock().unwrap().get(&entity).copied()
+        }
+
+        fn native_resources(&self) -> ChildWindowNativeResources {
+            ChildWindowNativeResources {
+                child_windows: self.rects.lock().unwrap().len(),
+                event_monitors: 0,
+            }
+        }
+    }
+
+    /// Creates a headless app running [`ChildWindowPlugin`] with `backend`.
+    pub(crate) fn mock_app(backend: Arc<MockBackend>) -> App {
+        let mut app = App::new();
+        app
+            .add_plugins((
+                TaskPoolPlugin::default(),
+                InputPlugin,
+                WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    ..Default::default()
+                },
+                ChildWindowPlugin {
+                    backend: Some(backend),
+                    ..Default::default()
+                },
+            ))
+            .insert_non_send_resource(WinitWindows::default());
+        app
+    }
+
+    #[test]
+    fn attaches_and_detaches_child_windows() {
+        let backend = Arc::new(MockBackend::default());
+        let mut app = mock_app(backend.clone());
+        let parent = app.world_mut().spawn(Window::default()).id();
+        let child = app.world_mut().spawn((Window::default(), ParentWindow(parent))).id();
+        app.update();
+        assert!(!app.world().entity(child).contains::<UnInitializeWindow>());
+        assert!(backend.rects.lock().unwrap().contains_key(&child));
+
+        app.world_mut().entity_mut(child).remove::<ParentWindow>();
+        app.update();
+        assert!(!backend.rects.lock().unwrap().contains_key(&child));
+    }
+
+    #[test]
+    fn applies_requested_changes_after_attaching() {
+        let backend = Arc::new(MockBackend::default());
+        let mut app = mock_app(backend.clone());
+        let parent = app.world_mut().spawn(Window::default()).id();
+        let child = app
+            .world_mut()
+            .commands()
+            .spawn_child_window(parent, Window::default())
+            .move_to(Vec2::new(10., 20.))
+            .raise()
+            .id();
+        app.update();
+        assert_eq!(backend.rects.lock().unwrap()[&child], Rect::new(10., 20., 10. + 1280., 20. + 720.));
+        assert_eq!(*backend.raised.lock().unwrap(), vec![child]);
+
+        app.world_mut().commands().entity(child).resize(Vec2::new(400., 300.));
+        app.update();
+        assert_eq!(backend.rects.lock().unwrap()[&child], Rect::new(10., 20., 410., 320.));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::util::{log_warn, retain_events};
+use crate::{ChildWindowSettings, ParentWindow};
+use bevy::app::{App, First, Plugin};
+use bevy::ecs::event::{EventCursor, EventUpdates};
+use bevy::prelude::{Commands, Component, Entity, Events, IntoScheduleConfigs, Local, Query, Reflect, ReflectComponent, ReflectDefault, ReflectDeserialize, ReflectResource, ReflectSerialize, Res, ResMut, Resource, With};
+use bevy::window::{Window, WindowCloseRequested};
+use serde::{Deserialize, Serialize};
+
+pub(crate) struct ChildWindowClosePlugin;
+
+impl Plugin for ChildWindowClosePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<ParentClosePolicy>()
+            .register_type::<DefaultParentClosePolicy>()
+            .init_resource::<DefaultParentClosePolicy>()
+            .add_systems(First, apply_parent_close_policies.after(EventUpdates));
+    }
+}
+
+/// Specifies what happens to the child window when the user requests to close its parent window.
+///
+/// The policy is applied when [`WindowCloseRequested`] is fired for the parent window.
+/// Child windows without this component follow [`DefaultParentClosePolicy`].
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::window::PrimaryWindow;
+/// use bevy_child_window::prelude::*;
+///
+/// fn spawn_child_window(
+///     mut commands: Commands,
+///     parent: Query<Entity, With<PrimaryWindow>>,
+/// ){
+///     commands.spawn((
+///         ParentWindow(parent.single().expect("Parent not found")),
+///         // The parent window cannot be closed while this child window is open.
+///         ParentClosePolicy::PreventParentClose,
+///         Window::default(),
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub enum ParentClosePolicy {
+    /// The child window is closed together with the parent window.
+    #[default]
+    CloseChildren,
+    /// The child window is detached and becomes a top-level window.
+    DetachToTopLevel,
+    /// The child window is moved into the specified window.
+    ///
+    /// If the entity is not a window, the child window is closed instead.
+    ReparentTo(Entity),
+    /// The parent window is not closed while this child window exists.
+    ///
+    /// The [`WindowCloseRequested`] event of the parent window is filtered out.
+    /// This also applies to the ancestor windows that would close this child window together with them,
+    /// e.g. the grandparent window when the parent window follows [`ParentClosePolicy::CloseChildren`].
+    PreventParentClose,
+}
+
+/// The [`ParentClosePolicy`] of the child windows that don't have their own.
+#[derive(Resource, Reflect, Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[reflect(Resource, Default)]
+pub struct DefaultParentClosePolicy(pub ParentClosePolicy);
+
+fn apply_parent_close_policies(
+    mut commands: Commands,
+    mut cursor: Local<EventCursor<WindowCloseRequested>>,
+    events: Option<ResMut<Events<WindowCloseRequested>>>,
+    default_policy: Res<DefaultParentClosePolicy>,
+    settings: Res<ChildWindowSettings>,
+    children: Query<(Entity, &ParentWindow, Option<&ParentClosePolicy>), With<Window>>,
+    windows: Query<(), With<Window>>,
+) {
+    let Some(mut events) = events else {
+        return;
+    };
+    let policy_of = |policy: Option<&ParentClosePolicy>| policy.copied().unwrap_or(default_policy.0);
+    let mut prevented = Vec::new();
+    let mut closing = Vec::new();
+    for WindowCloseRequested { window } in cursor.clone().read(&events) {
+        let start = closing.len();
+        closing.push(*window);
+        // The nested child windows closed with their parent are handled the same as their parent.
+        loop {
+            let closed_children = children
+                .iter()
+                .filter(|(entity, parent, policy)| {
+                    closing.contains(&parent.0) && !closing.contains(entity) && closes_with_parent(*entity, policy_of(*policy), &closing, &windows)
+                })
+                .map(|(entity, ..)| entity)
+                .collect::<Vec<_>>();
+            if closed_children.is_empty() {
+                break;
+            }
+            closing.extend(closed_children);
+        }
+        let prevents_close = |entity: &Entity| {
+            children
+                .get(*entity)
+                .is_ok_and(|(_, _, policy)| policy_of(policy) == ParentClosePolicy::PreventParentClose)
+        };
+        if closing[start + 1..].iter().any(prevents_close) {
+            closing.truncate(start);
+            prevented.push(*window);
+        }
+    }
+    retain_events(&mut events, &mut cursor, |e| !prevented.contains(&e.window));
+    for (entity, ParentWindow(parent), policy) in children.iter() {
+        if !closing.contains(parent) {
+            continue;
+        }
+        match policy_of(policy) {
+            ParentClosePolicy::DetachToTopLevel => {
+                commands.entity(entity).remove::<ParentWindow>();
+            }
+            ParentClosePolicy::ReparentTo(new_parent) if can_reparent(entity, new_parent, &closing, &windows) => {
+                commands
+                    .entity(entity)
+                    .remove::<ParentWindow>()
+                    .insert(ParentWindow(new_parent));
+            }
+            ParentClosePolicy::ReparentTo(new_parent) => {
+                log_warn!(settings.log_level, "The child window {entity} cannot be moved into {new_parent}, so it is closed with its parent window.");
+                commands.entity(entity).despawn();
+            }
+            ParentClosePolicy::CloseChildren | ParentClosePolicy::PreventParentClose => {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+fn closes_with_parent(
+    entity: Entity,
+    policy: ParentClosePolicy,
+    closing: &[Entity],
+    windows: &Query<(), With<Window>>,
+) -> bool {
+    match policy {
+        ParentClosePolicy::DetachToTopLevel => false,
+        ParentClosePolicy::ReparentTo(new_parent) => !can_reparent(entity, new_parent, closing, windows),
+        // A closed child window that prevents the close keeps all of its ancestors being closed together open.
+        ParentClosePolicy::CloseChildren | ParentClosePolicy::PreventParentClose => true,
+    }
+}
+
+fn can_reparent(
+    entity: Entity,
+    new_parent: Entity,
+    closing: &[Entity],
+    windows: &Query<(), With<Window>>,
+) -> bool {
+    new_parent != entity && !closing.contains(&new_parent) && windows.contains(new_parent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::{mock_app, MockBackend};
+    use std::sync::Arc;
+
+    #[test]
+    fn nested_child_window_prevents_closing_its_ancestors() {
+        let mut app = mock_app(Arc::new(MockBackend::default()));
+        let grandparent = app.world_mut().spawn(Window::default()).id();
+        let parent = app.world_mut().spawn((Window::default(), ParentWindow(grandparent))).id();
+        let child = app
+            .world_mut()
+            .spawn((Window::default(), ParentWindow(parent), ParentClosePolicy::PreventParentClose))
+            .id();
+        app.update();
+
+        app.world_mut().send_event(WindowCloseRequested { window: grandparent });
+        app.update();
+        app.update();
+        for window in [grandparent, parent, child] {
+            assert!(app.world().get_entity(window).is_ok());
+        }
+
+        app.world_mut().entity_mut(parent).insert(ParentClosePolicy::DetachToTopLevel);
+        app.world_mut().send_event(WindowCloseRequested { window: grandparent });
+        // `bevy_window` despawns the closed window in the next frame.
+        app.update();
+        app.update();
+        assert!(app.world().get_entity(grandparent).is_err());
+        assert!(app.world().get::<ParentWindow>(parent).is_none());
+        assert!(app.world().get_entity(child).is_ok());
+    }
+}
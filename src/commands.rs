@@ -0,0 +1,199 @@
+use crate::backend::ActiveBackend;
+use crate::{ChildWindowSystems, ParentWindow, StartChildWindowDrag, UnInitializeWindow};
+use bevy::app::{App, Plugin, PostUpdate};
+use bevy::math::{Rect, Vec2};
+use bevy::prelude::{Commands, Component, Entity, EntityCommands, EntityWorldMut, IntoScheduleConfigs, NonSend, Query, Res, Window, Without};
+use bevy::winit::WinitWindows;
+
+pub(crate) struct ChildWindowCommandsPlugin;
+
+impl Plugin for ChildWindowCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, apply_requests.in_set(ChildWindowSystems::Layout));
+    }
+}
+
+/// Provides the commands to control child windows.
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_child_window::prelude::*;
+///
+/// fn start_drag(
+///     mut commands: Commands,
+///     child_windows: Query<Entity, With<ParentWindow>>,
+/// ){
+///     for entity in child_windows.iter() {
+///         commands.start_drag(entity);
+///     }
+/// }
+/// ```
+pub trait ChildWindowCommands {
+    /// Starts moving the child window with the current mouse drag.
+    ///
+    /// See [`StartChildWindowDrag`].
+    fn start_drag(&mut self, entity: Entity) -> &mut Self;
+
+    /// Spawns `window` as a child window of `parent`.
+    ///
+    /// The returned [`EntityCommands`] can be used to add more components or to control the child window
+    /// with [`ChildWindowEntityCommands`].
+    fn spawn_child_window(&mut self, parent: Entity, window: Window) -> EntityCommands<'_>;
+}
+
+impl ChildWindowCommands for Commands<'_, '_> {
+    fn start_drag(&mut self, entity: Entity) -> &mut Self {
+        self.send_event(StartChildWindowDrag(entity));
+        self
+    }
+
+    fn spawn_child_window(&mut self, parent: Entity, window: Window) -> EntityCommands<'_> {
+        self.spawn((ParentWindow(parent), window))
+    }
+}
+
+/// Provides the commands to control the child window of the entity.
+///
+/// The changes are applied by the plugin once the child window is attached to its parent window,
+/// so they can be issued right after spawning it.
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::window::PrimaryWindow;
+/// use bevy_child_window::prelude::*;
+///
+/// fn spawn_child_window(
+///     mut commands: Commands,
+///     parent: Query<Entity, With<PrimaryWindow>>,
+/// ){
+///     commands
+///         .spawn_child_window(parent.single().expect("Parent not found"), Window::default())
+///         .move_to(Vec2::new(100., 50.))
+///         .resize(Vec2::new(400., 300.))
+///         .raise();
+/// }
+/// ```
+pub trait ChildWindowEntityCommands {
+    /// Moves the top-left corner of the child window to `position` in logical pixels relative to the top-left corner of the parent window's client area.
+    fn move_to(&mut self, position: Vec2) -> &mut Self;
+
+    /// Resizes the client area of the child window to `size` in logical pixels, the same as [`Window::size`].
+    fn resize(&mut self, size: Vec2) -> &mut Self;
+
+    /// Brings the child window to the front of its sibling child windows.
+    fn raise(&mut self) -> &mut Self;
+
+    /// Detaches the child window from its parent window so that it becomes a top-level window.
+    fn detach(&mut self) -> &mut Self;
+
+    /// Moves the child window into `parent`.
+    ///
+    /// The new parent is validated the same as inserting [`ParentWindow`].
+    fn reparent(&mut self, parent: Entity) -> &mut Self;
+}
+
+impl ChildWindowEntityCommands for EntityCommands<'_> {
+    fn move_to(&mut self, position: Vec2) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            request(&mut entity, |requests| requests.position = Some(position));
+        })
+    }
+
+    fn resize(&mut self, size: Vec2) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            request(&mut entity, |requests| requests.size = Some(size));
+        })
+    }
+
+    fn raise(&mut self) -> &mut Self {
+        self.queue(|mut entity: EntityWorldMut| {
+            request(&mut entity, |requests| requests.raise = true);
+        })
+    }
+
+    fn detach(&mut self) -> &mut Self {
+        self.remove::<ParentWindow>()
+    }
+
+    fn reparent(&mut self, parent: Entity) -> &mut Self {
+        // Removing it first lets the child window be attached again from scratch.
+        self
+            .remove::<ParentWindow>()
+            .insert(ParentWindow(parent))
+    }
+}
+
+/// The changes requested by [`ChildWindowEntityCommands`] that are waiting for the child window to be attached.
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq)]
+struct ChildWindowRequests {
+    position: Option<Vec2>,
+    size: Option<Vec2>,
+    raise: bool,
+}
+
+fn request(entity: &mut EntityWorldMut, f: impl FnOnce(&mut ChildWindowRequests)) {
+    if let Some(mut requests) = entity.get_mut::<ChildWindowRequests>() {
+        f(&mut requests);
+    } else {
+        let mut requests = ChildWindowRequests::default();
+        f(&mut requests);
+        entity.insert(requests);
+    }
+}
+
+fn apply_requests(
+    mut commands: Commands,
+    windows: Query<(Entity, &ParentWindow, &Window, &ChildWindowRequests), Without<UnInitializeWindow>>,
+    backend: Res<ActiveBackend>,
+    winit_windows: NonSend<WinitWindows>,
+) {
+    for (entity, ParentWindow(parent), window, requests) in windows.iter() {
+        if requests.position.is_some() || requests.size.is_some() {
+            // A resize alone keeps the current position, which is only known once the native window reports it.
+            let Some(position) = requests
+                .position
+                .or_else(|| backend.query_rect(&winit_windows, entity, *parent).map(|rect| rect.min))
+            else {
+                continue;
+            };
+            let size = requests.size.unwrap_or(window.size());
+            backend.set_rect(&winit_windows, entity, *parent, Rect::from_corners(position, position + size));
+        }
+        if requests.raise {
+            backend.raise(&winit_windows, entity);
+        }
+        commands.entity(entity).remove::<ChildWindowRequests>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::{mock_app, MockBackend};
+    use std::sync::Arc;
+
+    #[test]
+    fn move_keeps_size_of_client_area() {
+        let backend = Arc::new(MockBackend::default());
+        let mut app = mock_app(backend.clone());
+        let parent = app.world_mut().spawn(Window::default()).id();
+        let child = app
+            .world_mut()
+            .commands()
+            .spawn_child_window(parent, Window::default())
+            .resize(Vec2::new(400., 300.))
+            .id();
+        app.update();
+        assert_eq!(backend.rects.lock().unwrap()[&child].size(), Vec2::new(400., 300.));
+
+        // The window reports the requested size back.
+        app.world_mut().get_mut::<Window>(child).unwrap().resolution.set(400., 300.);
+        app.world_mut().commands().entity(child).move_to(Vec2::new(30., 40.));
+        app.update();
+        app.world_mut().commands().entity(child).move_to(Vec2::new(50., 60.));
+        app.update();
+        assert_eq!(backend.rects.lock().unwrap()[&child], Rect::new(50., 60., 450., 360.));
+    }
+}
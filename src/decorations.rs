@@ -0,0 +1,57 @@
+use crate::backend::ActiveBackend;
+use crate::{ParentWindow, UnInitializeWindow};
+use bevy::app::{App, First, Plugin};
+use bevy::prelude::{Changed, Commands, Component, Entity, NonSend, Query, Res, With, Without};
+use bevy::window::Window;
+use bevy::winit::WinitWindows;
+
+pub(crate) struct ChildWindowDecorationsPlugin;
+
+impl Plugin for ChildWindowDecorationsPlugin {
+    fn build(&self, app: &mut App) {
+        // `bevy_winit` applies the changes of `Window` in `Last`,
+        // so the child window rules are re-applied on top of them at the beginning of the next frame.
+        app.add_systems(First, reapply_decorations);
+    }
+}
+
+/// The decoration fields of [`Window`] last applied to the child window.
+#[derive(Component, Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) struct AppliedDecorations {
+    decorations: bool,
+    resizable: bool,
+    titlebar_shown: bool,
+    titlebar_show_buttons: bool,
+    titlebar_show_title: bool,
+}
+
+impl From<&Window> for AppliedDecorations {
+    fn from(window: &Window) -> Self {
+        Self {
+            decorations: window.decorations,
+            resizable: window.resizable,
+            titlebar_shown: window.titlebar_shown,
+            titlebar_show_buttons: window.titlebar_show_buttons,
+            titlebar_show_title: window.titlebar_show_title,
+        }
+    }
+}
+
+fn reapply_decorations(
+    mut commands: Commands,
+    backend: Res<ActiveBackend>,
+    winit_windows: NonSend<WinitWindows>,
+    windows: Query<
+        (Entity, &Window, Option<&AppliedDecorations>),
+        (With<ParentWindow>, Without<UnInitializeWindow>, Changed<Window>),
+    >,
+) {
+    for (entity, window, applied) in windows.iter() {
+        let decorations = AppliedDecorations::from(window);
+        if applied == Some(&decorations) {
+            continue;
+        }
+        backend.apply_decorations(&winit_windows, entity, window);
+        commands.entity(entity).insert(decorations);
+    }
+}
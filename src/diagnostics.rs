@@ -0,0 +1,65 @@
+use crate::backend::ActiveBackend;
+use bevy::app::{App, First, Plugin};
+use bevy::prelude::{DetectChangesMut, NonSend, Reflect, ReflectResource, Res, ResMut, Resource};
+use bevy::winit::WinitWindows;
+
+pub(crate) struct ChildWindowDiagnosticsPlugin;
+
+impl Plugin for ChildWindowDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<ChildWindowNativeResources>()
+            .init_resource::<ChildWindowNativeResources>()
+            .add_systems(First, update_native_resources);
+    }
+}
+
+/// The number of native resources held for child windows.
+///
+/// It is updated at the beginning of each frame, and can be used to check that closing child windows releases their native resources.
+/// The counts are reported by [`ChildWindowBackend::native_resources`](crate::ChildWindowBackend::native_resources), so they are always zero on unsupported platforms.
+#[derive(Resource, Reflect, Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[reflect(Resource)]
+pub struct ChildWindowNativeResources {
+    /// The number of child windows whose native handlers are installed.
+    pub child_windows: usize,
+    /// The number of native event monitors installed for parent windows.
+    pub event_monitors: usize,
+}
+
+// `WinitWindows` keeps the system on the main thread, which owns the native resources on macOS.
+fn update_native_resources(
+    mut resources: ResMut<ChildWindowNativeResources>,
+    backend: Res<ActiveBackend>,
+    _winit_windows: NonSend<WinitWindows>,
+) {
+    resources.set_if_neq(backend.native_resources());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::{mock_app, MockBackend};
+    use crate::ParentWindow;
+    use bevy::window::Window;
+    use std::sync::Arc;
+
+    #[test]
+    fn despawning_child_windows_releases_native_resources() {
+        let mut app = mock_app(Arc::new(MockBackend::default()));
+        let parent = app.world_mut().spawn(Window::default()).id();
+        let children = (0..3)
+            .map(|_| app.world_mut().spawn((Window::default(), ParentWindow(parent))).id())
+            .collect::<Vec<_>>();
+        app.update();
+        app.update();
+        assert_eq!(app.world().resource::<ChildWindowNativeResources>().child_windows, 3);
+
+        for child in children {
+            app.world_mut().despawn(child);
+        }
+        app.update();
+        app.update();
+        assert_eq!(*app.world().resource::<ChildWindowNativeResources>(), ChildWindowNativeResources::default());
+    }
+}
@@ -0,0 +1,618 @@
+use crate::backend::ActiveBackend;
+use crate::{ChildWindowDragEnded, ChildWindowSystems, ChildWindowDragged, ParentWindow, UnInitializeWindow};
+use bevy::app::{App, Plugin, PostUpdate, PreUpdate};
+use bevy::math::{Rect, Vec2};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Commands, Component, Entity, Event, EventReader, EventWriter, IntoScheduleConfigs, Local, NonSend, ParamSet, Query, Reflect, ReflectComponent, ReflectDefault, ReflectDeserialize, ReflectSerialize, Res, Window, With, Without};
+use bevy::winit::WinitWindows;
+use serde::{Deserialize, Serialize};
+
+/// The distance in logical pixels from the edges of the parent window within which a drop docks the child window to the edge.
+const EDGE_DROP_MARGIN: f32 = 32.;
+
+/// The fraction of a docked leaf near its edges within which a drop splits the leaf.
+const SPLIT_DROP_FRACTION: f32 = 0.25;
+
+pub(crate) struct ChildWindowDockPlugin;
+
+impl Plugin for ChildWindowDockPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<ChildWindowDockTree>()
+            .add_event::<ChildWindowDockPreview>()
+            .add_systems(PreUpdate, drop_dragged_windows.after(ChildWindowSystems::Interaction))
+            .add_systems(PostUpdate, layout_docked_windows.in_set(ChildWindowSystems::Layout));
+    }
+}
+
+/// The dock layout of a parent window.
+///
+/// Insert this into the parent window to enable docking.
+/// The tree covers the whole client area of the parent window;
+/// each [`DockNode::Split`] divides its area in two, and each [`DockNode::Leaf`] holds a child window
+/// that is moved and resized to fill its area whenever the tree or the size of the parent window changes.
+/// [`DockNode::Empty`] is the remaining area where the undocked child windows float.
+///
+/// While the user drags a child window of the parent window, [`ChildWindowDockPreview`] is fired with the drop zone under the drop point,
+/// which is the center of the top edge of the dragged child window, i.e. its title bar.
+/// When the drag ends, the child window is docked into the zone, or undocked if it is dropped outside of any zone.
+///
+/// The tree can be saved and restored with serde.
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::window::PrimaryWindow;
+/// use bevy_child_window::prelude::*;
+///
+/// fn setup(
+///     mut commands: Commands,
+///     parent: Query<Entity, With<PrimaryWindow>>,
+/// ){
+///     let parent = parent.single().expect("Parent not found");
+///     let panel = commands.spawn((
+///         ParentWindow(parent),
+///         Window::default(),
+///     )).id();
+///     let mut tree = ChildWindowDockTree::default();
+///     tree.dock(panel, DockTarget::Edge(DockSide::Left), 0.25);
+///     commands.entity(parent).insert(tree);
+/// }
+/// ```
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[reflect(opaque)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub struct ChildWindowDockTree {
+    /// The root node covering the client area of the parent window.
+    pub root: DockNode,
+}
+
+/// The node of [`ChildWindowDockTree`].
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub enum DockNode {
+    /// The area not occupied by any docked child window.
+    #[default]
+    Empty,
+    /// The area filled by the child window.
+    Leaf(Entity),
+    /// The area divided in two along `axis`.
+    Split {
+        /// The direction in which the area is divided.
+        axis: DockAxis,
+        /// The fraction of the area given to `first`, from `0.0` to `1.0`.
+        ///
+        /// Changing this moves the splitter.
+        ratio: f32,
+        /// The left or top node.
+        first: Box<DockNode>,
+        /// The right or bottom node.
+        second: Box<DockNode>,
+    },
+}
+
+/// The direction in which [`DockNode::Split`] divides its area.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DockAxis {
+    /// The nodes are placed side by side.
+    Horizontal,
+    /// The nodes are stacked from top to bottom.
+    Vertical,
+}
+
+/// The side of an area.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DockSide {
+    /// The left side.
+    Left,
+    /// The right side.
+    Right,
+    /// The top side.
+    Top,
+    /// The bottom side.
+    Bottom,
+}
+
+/// Where a child window is docked.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DockTarget {
+    /// Along the edge of the parent window, splitting the whole tree.
+    Edge(DockSide),
+    /// Along the side of the docked child window, splitting its leaf.
+    Split {
+        /// The docked child window whose leaf is split.
+        leaf: Entity,
+        /// The side of the leaf where the child window is placed.
+        side: DockSide,
+    },
+    /// Into the [`DockNode::Empty`] area.
+    Fill,
+}
+
+/// The drop zone under a dragged child window.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DockDropZone {
+    /// Where the child window is docked if dropped.
+    pub target: DockTarget,
+    /// The fraction of the split area given to the child window.
+    pub ratio: f32,
+    /// The rect the child window would occupy, in logical pixels relative to the top-left corner of the parent window's client area.
+    pub rect: Rect,
+}
+
+/// Fired when the drop zone under a dragged child window changes.
+///
+/// Use this to draw a preview of where the child window will be docked.
+#[derive(Event, Debug, Copy, Clone, PartialEq)]
+pub struct ChildWindowDockPreview {
+    /// The entity of the dragged child window.
+    pub window: Entity,
+    /// The drop zone, or `None` if the child window leaves all the drop zones or the drag ends.
+    pub zone: Option<DockDropZone>,
+}
+
+impl ChildWindowDockTree {
+    /// Returns true if `window` is docked in this tree.
+    pub fn contains(&self, window: Entity) -> bool {
+        self.root.contains(window)
+    }
+
+    /// Docks `window` into `target`, giving it `ratio` of the split area.
+    ///
+    /// If `window` is already docked, it is moved.
+    /// Returns false and leaves the tree unchanged if `target` doesn't exist in the tree.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_child_window::{ChildWindowDockTree, DockSide, DockTarget};
+    ///
+    /// let left = Entity::from_raw(1);
+    /// let bottom = Entity::from_raw(2);
+    /// let mut tree = ChildWindowDockTree::default();
+    /// assert!(tree.dock(left, DockTarget::Edge(DockSide::Left), 0.25));
+    /// assert!(tree.dock(bottom, DockTarget::Split { leaf: left, side: DockSide::Bottom }, 0.5));
+    ///
+    /// let layout = tree.layout(Vec2::new(800., 600.));
+    /// assert_eq!(layout, vec![
+    ///     (left, Rect::new(0., 0., 200., 300.)),
+    ///     (bottom, Rect::new(0., 300., 200., 600.)),
+    /// ]);
+    ///
+    /// assert!(tree.undock(left));
+    /// assert_eq!(tree.layout(Vec2::new(800., 600.)), vec![(bottom, Rect::new(0., 0., 200., 600.))]);
+    /// ```
+    pub fn dock(&mut self, window: Entity, target: DockTarget, ratio: f32) -> bool {
+        let mut tree = self.clone();
+        tree.undock(window);
+        let ratio = ratio.clamp(0., 1.);
+        let docked = match target {
+            DockTarget::Edge(side) => {
+                let root = std::mem::take(&mut tree.root);
+                tree.root = DockNode::split(side, ratio, window, root);
+                true
+            }
+            DockTarget::Split { leaf, side } => tree.root.split_leaf(leaf, side, ratio, window),
+            DockTarget::Fill => tree.root.fill(window),
+        };
+        if docked {
+            *self = tree;
+        }
+        docked
+    }
+
+    /// Removes `window` from this tree; the area of its leaf is given to the sibling node.
+    ///
+    /// Returns false if `window` isn't docked.
+    pub fn undock(&mut self, window: Entity) -> bool {
+        if self.root == DockNode::Leaf(window) {
+            self.root = DockNode::Empty;
+            return true;
+        }
+        self.root.remove(window)
+    }
+
+    /// Returns the rects of the docked child windows in the client area of `parent_size`.
+    pub fn layout(&self, parent_size: Vec2) -> Vec<(Entity, Rect)> {
+        let mut areas = Vec::new();
+        self.root.layout(Rect::from_corners(Vec2::ZERO, parent_size), &mut areas);
+        areas
+            .into_iter()
+            .filter_map(|(window, rect)| Some((window?, rect)))
+            .collect()
+    }
+
+    /// Returns the drop zone at `point` for `window`, whose current size is `window_size`.
+    ///
+    /// `point` is in logical pixels relative to the top-left corner of the parent window's client area.
+    pub fn drop_zone(
+        &self,
+        window: Entity,
+        window_size: Vec2,
+        point: Vec2,
+        parent_size: Vec2,
+    ) -> Option<DockDropZone> {
+        let client_area = Rect::from_corners(Vec2::ZERO, parent_size);
+        if !client_area.contains(point) {
+            return None;
+        }
+        let mut tree = self.clone();
+        tree.undock(window);
+        let target = if let Some(side) = nearest_side(client_area, point, EDGE_DROP_MARGIN) {
+            DockTarget::Edge(side)
+        } else {
+            let mut areas = Vec::new();
+            tree.root.layout(client_area, &mut areas);
+            let (leaf, area) = areas.into_iter().find(|(_, area)| area.contains(point))?;
+            match leaf {
+                Some(leaf) => {
+                    let margin = area.size().min_element() * SPLIT_DROP_FRACTION;
+                    DockTarget::Split {
+                        leaf,
+                        side: nearest_side(area, point, margin)?,
+                    }
+                }
+                None => DockTarget::Fill,
+            }
+        };
+        let area = tree.target_area(target, parent_size)?;
+        let ratio = dock_ratio(target, window_size, area.size());
+        tree.dock(window, target, ratio);
+        let (_, rect) = tree
+            .layout(parent_size)
+            .into_iter()
+            .find(|(docked, _)| *docked == window)?;
+        Some(DockDropZone { target, ratio, rect })
+    }
+
+    fn target_area(&self, target: DockTarget, parent_size: Vec2) -> Option<Rect> {
+        let client_area = Rect::from_corners(Vec2::ZERO, parent_size);
+        match target {
+            DockTarget::Edge(_) => Some(client_area),
+            DockTarget::Split { leaf, .. } => {
+                self.layout(parent_size)
+                    .into_iter()
+                    .find_map(|(window, rect)| (window == leaf).then_some(rect))
+            }
+            DockTarget::Fill => {
+                let mut areas = Vec::new();
+                self.root.layout(client_area, &mut areas);
+                areas
+                    .into_iter()
+                    .find_map(|(window, rect)| window.is_none().then_some(rect))
+            }
+        }
+    }
+}
+
+impl DockNode {
+    fn split(side: DockSide, ratio: f32, window: Entity, other: DockNode) -> Self {
+        let window = Box::new(DockNode::Leaf(window));
+        let other = Box::new(other);
+        match side {
+            DockSide::Left => Self::Split { axis: DockAxis::Horizontal, ratio, first: window, second: other },
+            DockSide::Right => Self::Split { axis: DockAxis::Horizontal, ratio: 1. - ratio, first: other, second: window },
+            DockSide::Top => Self::Split { axis: DockAxis::Vertical, ratio, first: window, second: other },
+            DockSide::Bottom => Self::Split { axis: DockAxis::Vertical, ratio: 1. - ratio, first: other, second: window },
+        }
+    }
+
+    fn contains(&self, window: Entity) -> bool {
+        match self {
+            Self::Empty => false,
+            Self::Leaf(leaf) => *leaf == window,
+            Self::Split { first, second, .. } => first.contains(window) || second.contains(window),
+        }
+    }
+
+    fn split_leaf(&mut self, leaf: Entity, side: DockSide, ratio: f32, window: Entity) -> bool {
+        match self {
+            Self::Leaf(l) if *l == leaf => {
+                *self = Self::split(side, ratio, window, Self::Leaf(leaf));
+                true
+            }
+            Self::Empty | Self::Leaf(_) => false,
+            Self::Split { first, second, .. } => {
+                first.split_leaf(leaf, side, ratio, window) || second.split_leaf(leaf, side, ratio, window)
+            }
+        }
+    }
+
+    fn fill(&mut self, window: Entity) -> bool {
+        match self {
+            Self::Empty => {
+                *self = Self::Leaf(window);
+                true
+            }
+            Self::Leaf(_) => false,
+            Self::Split { first, second, .. } => first.fill(window) || second.fill(window),
+        }
+    }
+
+    fn remove(&mut self, window: Entity) -> bool {
+        let Self::Split { first, second, .. } = self else {
+            return false;
+        };
+        if **first == Self::Leaf(window) {
+            *self = std::mem::take(&mut **second);
+            true
+        } else if **second == Self::Leaf(window) {
+            *self = std::mem::take(&mut **first);
+            true
+        } else {
+            first.remove(window) || second.remove(window)
+        }
+    }
+
+    fn leaves(&self, leaves: &mut Vec<Entity>) {
+        match self {
+            Self::Empty => {}
+            Self::Leaf(window) => leaves.push(*window),
+            Self::Split { first, second, .. } => {
+                first.leaves(leaves);
+                second.leaves(leaves);
+            }
+        }
+    }
+
+    fn layout(&self, area: Rect, areas: &mut Vec<(Option<Entity>, Rect)>) {
+        match self {
+            Self::Empty => areas.push((None, area)),
+            Self::Leaf(window) => areas.push((Some(*window), area)),
+            Self::Split { axis, ratio, first, second } => {
+                let ratio = ratio.clamp(0., 1.);
+                let (first_area, second_area) = match axis {
+                    DockAxis::Horizontal => {
+                        let x = area.min.x + area.width() * ratio;
+                        (Rect::new(area.min.x, area.min.y, x, area.max.y), Rect::new(x, area.min.y, area.max.x, area.max.y))
+                    }
+                    DockAxis::Vertical => {
+                        let y = area.min.y + area.height() * ratio;
+                        (Rect::new(area.min.x, area.min.y, area.max.x, y), Rect::new(area.min.x, y, area.max.x, area.max.y))
+                    }
+                };
+                first.layout(first_area, areas);
+                second.layout(second_area, areas);
+            }
+        }
+    }
+}
+
+/// Returns the side of `area` nearest to `point` if it is within `margin`.
+fn nearest_side(area: Rect, point: Vec2, margin: f32) -> Option<DockSide> {
+    [
+        (DockSide::Left, point.x - area.min.x),
+        (DockSide::Right, area.max.x - point.x),
+        (DockSide::Top, point.y - area.min.y),
+        (DockSide::Bottom, area.max.y - point.y),
+    ]
+        .into_iter()
+        .filter(|(_, distance)| *distance <= margin)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(side, _)| side)
+}
+
+/// Returns the ratio that keeps the width or height of the docked child window, if possible.
+fn dock_ratio(target: DockTarget, window_size: Vec2, area_size: Vec2) -> f32 {
+    let side = match target {
+        DockTarget::Edge(side) | DockTarget::Split { side, .. } => side,
+        DockTarget::Fill => return 1.,
+    };
+    let ratio = match side {
+        DockSide::Left | DockSide::Right => window_size.x / area_size.x,
+        DockSide::Top | DockSide::Bottom => window_size.y / area_size.y,
+    };
+    if ratio.is_finite() {
+        ratio.clamp(0.1, 0.9)
+    } else {
+        0.5
+    }
+}
+
+/// The rect applied to the docked child window and the size of the parent window at that time.
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+struct DockedRect(Rect, Vec2);
+
+fn drop_dragged_windows(
+    mut commands: Commands,
+    mut dragged: EventReader<ChildWindowDragged>,
+    mut drag_ended: EventReader<ChildWindowDragEnded>,
+    mut previews: EventWriter<ChildWindowDockPreview>,
+    mut current_zones: Local<HashMap<Entity, DockDropZone>>,
+    mut trees: Query<(&mut ChildWindowDockTree, &Window)>,
+    children: Query<(&ParentWindow, &Window)>,
+) {
+    for ChildWindowDragged { window, rect } in dragged.read() {
+        let zone = drop_zone(*window, *rect, &trees, &children);
+        if current_zones.get(window) == zone.as_ref() {
+            continue;
+        }
+        match zone {
+            Some(zone) => current_zones.insert(*window, zone),
+            None => current_zones.remove(window),
+        };
+        previews.write(ChildWindowDockPreview {
+            window: *window,
+            zone,
+        });
+    }
+    for ChildWindowDragEnded { window, rect } in drag_ended.read() {
+        if current_zones.remove(window).is_some() {
+            previews.write(ChildWindowDockPreview {
+                window: *window,
+                zone: None,
+            });
+        }
+        let zone = drop_zone(*window, *rect, &trees, &children);
+        let Ok((ParentWindow(parent), _)) = children.get(*window) else {
+            continue;
+        };
+        let Ok((mut tree, _)) = trees.get_mut(*parent) else {
+            continue;
+        };
+        if let Some(DockDropZone { target, ratio, .. }) = zone {
+            tree.dock(*window, target, ratio);
+        } else if tree.contains(*window) {
+            tree.undock(*window);
+        }
+        // The child window was moved by the user, so its docked rect must be applied again.
+        commands.entity(*window).remove::<DockedRect>();
+    }
+}
+
+fn drop_zone(
+    window: Entity,
+    rect: Rect,
+    trees: &Query<(&mut ChildWindowDockTree, &Window)>,
+    children: &Query<(&ParentWindow, &Window)>,
+) -> Option<DockDropZone> {
+    let (ParentWindow(parent), child) = children.get(window).ok()?;
+    let (tree, parent_window) = trees.get(*parent).ok()?;
+    let point = Vec2::new(rect.center().x, rect.min.y);
+    tree.drop_zone(window, child.size(), point, parent_window.size())
+}
+
+fn layout_docked_windows(
+    mut commands: Commands,
+    mut windows: ParamSet<(
+        Query<(Entity, &mut ChildWindowDockTree, &Window)>,
+        Query<(&ParentWindow, Option<&DockedRect>), Without<UnInitializeWindow>>,
+    )>,
+    parents: Query<&ParentWindow>,
+    docked: Query<Entity, (With<DockedRect>, Without<UnInitializeWindow>)>,
+    backend: Res<ActiveBackend>,
+    winit_windows: NonSend<WinitWindows>,
+) {
+    let mut layouts = Vec::new();
+    for (parent, mut tree, window) in windows.p0().iter_mut() {
+        let mut leaves = Vec::new();
+        tree.root.leaves(&mut leaves);
+        for leaf in leaves {
+            if parents.get(leaf).is_ok_and(|ParentWindow(p)| *p == parent) {
+                continue;
+            }
+            // The child window was despawned or moved to another parent window.
+            tree.undock(leaf);
+        }
+        let parent_size = window.size();
+        layouts.extend(tree.layout(parent_size).into_iter().map(|(child, rect)| (parent, child, DockedRect(rect, parent_size))));
+    }
+    let mut laid_out = Vec::with_capacity(layouts.len());
+    let children = windows.p1();
+    for (parent, child, state) in layouts {
+        let Ok((_, applied)) = children.get(child) else {
+            continue;
+        };
+        laid_out.push(child);
+        if applied == Some(&state) {
+            continue;
+        }
+        backend.set_rect(&winit_windows, child, parent, state.0);
+        commands.entity(child).insert(state);
+    }
+    for entity in docked.iter() {
+        if !laid_out.contains(&entity) {
+            commands.entity(entity).remove::<DockedRect>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARENT_SIZE: Vec2 = Vec2::new(800., 600.);
+    const WINDOW_SIZE: Vec2 = Vec2::new(200., 300.);
+    const A: Entity = Entity::from_raw(1);
+    const B: Entity = Entity::from_raw(2);
+    const DRAGGED: Entity = Entity::from_raw(3);
+
+    fn docked(docks: &[(Entity, DockTarget, f32)]) -> ChildWindowDockTree {
+        let mut tree = ChildWindowDockTree::default();
+        for (window, target, ratio) in docks.iter().copied() {
+            assert!(tree.dock(window, target, ratio));
+        }
+        tree
+    }
+
+    fn drop_zone_at(tree: &ChildWindowDockTree, point: Vec2) -> Option<DockDropZone> {
+        tree.drop_zone(DRAGGED, WINDOW_SIZE, point, PARENT_SIZE)
+    }
+
+    #[test]
+    fn edge_of_parent_window_splits_whole_tree() {
+        let tree = docked(&[(A, DockTarget::Edge(DockSide::Left), 0.5)]);
+        assert_eq!(drop_zone_at(&tree, Vec2::new(790., 300.)), Some(DockDropZone {
+            target: DockTarget::Edge(DockSide::Right),
+            ratio: 0.25,
+            rect: Rect::new(600., 0., 800., 600.),
+        }));
+        assert_eq!(drop_zone_at(&tree, Vec2::new(200., 10.)), Some(DockDropZone {
+            target: DockTarget::Edge(DockSide::Top),
+            ratio: 0.5,
+            rect: Rect::new(0., 0., 800., 300.),
+        }));
+        assert_eq!(drop_zone_at(&tree, Vec2::new(-10., 300.)), None);
+    }
+
+    #[test]
+    fn side_of_leaf_splits_leaf() {
+        let tree = docked(&[(A, DockTarget::Edge(DockSide::Left), 0.5)]);
+        assert_eq!(drop_zone_at(&tree, Vec2::new(200., 550.)), Some(DockDropZone {
+            target: DockTarget::Split {
+                leaf: A,
+                side: DockSide::Bottom,
+            },
+            ratio: 0.5,
+            rect: Rect::new(0., 300., 400., 600.),
+        }));
+        // The center of the leaf is not a drop zone.
+        assert_eq!(drop_zone_at(&tree, Vec2::new(200., 300.)), None);
+    }
+
+    #[test]
+    fn empty_area_is_filled() {
+        let tree = docked(&[(A, DockTarget::Edge(DockSide::Left), 0.5)]);
+        assert_eq!(drop_zone_at(&tree, Vec2::new(600., 300.)), Some(DockDropZone {
+            target: DockTarget::Fill,
+            ratio: 1.,
+            rect: Rect::new(400., 0., 800., 600.),
+        }));
+    }
+
+    #[test]
+    fn dragged_window_is_undocked_before_finding_drop_zone() {
+        let tree = docked(&[(DRAGGED, DockTarget::Edge(DockSide::Left), 0.5)]);
+        assert_eq!(drop_zone_at(&tree, Vec2::new(200., 300.)), Some(DockDropZone {
+            target: DockTarget::Fill,
+            ratio: 1.,
+            rect: Rect::new(0., 0., 800., 600.),
+        }));
+    }
+
+    #[test]
+    fn undock_gives_area_to_sibling() {
+        let mut tree = docked(&[
+            (A, DockTarget::Edge(DockSide::Left), 0.5),
+            (B, DockTarget::Split { leaf: A, side: DockSide::Bottom }, 0.5),
+        ]);
+        assert!(tree.undock(A));
+        assert_eq!(tree.root, DockNode::Split {
+            axis: DockAxis::Horizontal,
+            ratio: 0.5,
+            first: Box::new(DockNode::Leaf(B)),
+            second: Box::new(DockNode::Empty),
+        });
+        assert!(!tree.undock(A));
+
+        assert!(tree.undock(B));
+        assert_eq!(tree.root, DockNode::Empty);
+    }
+
+    #[test]
+    fn dock_into_missing_leaf_leaves_tree_unchanged() {
+        let mut tree = docked(&[(A, DockTarget::Edge(DockSide::Left), 0.5)]);
+        let before = tree.clone();
+        assert!(!tree.dock(A, DockTarget::Split { leaf: B, side: DockSide::Top }, 0.5));
+        assert_eq!(tree, before);
+    }
+}
@@ -0,0 +1,379 @@
+use crate::backend::{ActiveBackend, SharedState};
+use crate::{ChildWindowLock, ChildWindowSystems, ParentWindow, TitleBarHeight};
+use bevy::app::{App, Plugin, PostUpdate};
+use bevy::math::{Rect, Vec2};
+use bevy::prelude::{Added, Changed, Component, DetectChangesMut, Entity, Event, EventReader, EventWriter, IntoScheduleConfigs, NonSend, Or, Query, Reflect, ReflectComponent, ReflectDefault, RemovedComponents, Res, With};
+use bevy::window::Window;
+use bevy::winit::WinitWindows;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+pub(crate) struct ChildWindowDragPlugin;
+
+impl Plugin for ChildWindowDragPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<ChildWindowDragRegions>()
+            .add_event::<StartChildWindowDrag>()
+            .add_systems(PostUpdate, (
+                sync_drag_hit_tests.in_set(ChildWindowSystems::Sync),
+                (
+                    take_drag_move_requests,
+                    start_drags,
+                )
+                    .chain(),
+            ));
+    }
+}
+
+/// Specifies the regions of the child window where a drag to move the window may start.
+///
+/// This is mainly for undecorated child windows that draw their own header.
+/// The regions are in logical pixels relative to the top-left corner of the child window's client area.
+///
+/// A drag also starts on the title bar,
+/// or anywhere if [`Window::movable_by_window_background`] of the child window is true.
+/// See [`DragStartHit`] for the full rules.
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::window::PrimaryWindow;
+/// use bevy_child_window::prelude::*;
+///
+/// fn spawn_child_window(
+///     mut commands: Commands,
+///     parent: Query<Entity, With<PrimaryWindow>>,
+/// ){
+///     commands.spawn((
+///         ParentWindow(parent.single().expect("Parent not found")),
+///         // The header of 30px height can be dragged.
+///         ChildWindowDragRegions::new([Rect::new(0., 0., 500., 30.)]),
+///         Window {
+///             decorations: false,
+///             ..Default::default()
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component, Default)]
+pub struct ChildWindowDragRegions {
+    /// The rects where a drag may start.
+    pub regions: Vec<Rect>,
+    /// If specified, a drag may also start where this returns true.
+    ///
+    /// The argument is the cursor position in the same coordinates as [`ChildWindowDragRegions::regions`].
+    #[reflect(ignore)]
+    pub hit_test: Option<Arc<dyn Fn(Vec2) -> bool + Send + Sync>>,
+}
+
+impl ChildWindowDragRegions {
+    /// Creates the drag regions from the rects.
+    pub fn new(regions: impl IntoIterator<Item=Rect>) -> Self {
+        Self {
+            regions: regions.into_iter().collect(),
+            hit_test: None,
+        }
+    }
+
+    /// Creates the drag regions from the hit-test callback.
+    pub fn from_hit_test(hit_test: impl Fn(Vec2) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            regions: Vec::new(),
+            hit_test: Some(Arc::new(hit_test)),
+        }
+    }
+
+    /// Returns true if a drag may start at `position`.
+    pub fn contains(&self, position: Vec2) -> bool {
+        self.regions.iter().any(|rect| rect.contains(position)) ||
+            self.hit_test.as_ref().is_some_and(|hit_test| hit_test(position))
+    }
+}
+
+impl Debug for ChildWindowDragRegions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChildWindowDragRegions")
+            .field("regions", &self.regions)
+            .field("hit_test", &self.hit_test.is_some())
+            .finish()
+    }
+}
+
+/// Starts moving the child window with the current mouse drag.
+///
+/// This is useful for custom-drawn title bars, such as `bevy_ui` headers, which begin a move on `Pointer<DragStart>`.
+/// The move follows the cursor until the left mouse button is released, and is clamped to the parent window as well as the one started by the user.
+///
+/// Calling [`Window::start_drag_move`] on a child window also sends this event.
+/// It is ignored while the position of the child window is locked by [`ChildWindowLock`].
+///
+/// See also [`ChildWindowCommands::start_drag`](crate::ChildWindowCommands::start_drag).
+#[derive(Event, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct StartChildWindowDrag(pub Entity);
+
+/// The state of a child window at the point where the left mouse button is pressed.
+///
+/// All backends use this to decide whether the press starts moving the child window.
+///
+/// # Example
+/// ```
+/// use bevy_child_window::DragStartHit;
+///
+/// // The title bar always starts a move.
+/// assert!(DragStartHit { in_title_bar: true, ..Default::default() }.starts_drag());
+/// // The background only starts a move if the child window itself is movable by its background.
+/// assert!(!DragStartHit::default().starts_drag());
+/// assert!(DragStartHit { movable_by_window_background: true, ..Default::default() }.starts_drag());
+/// assert!(DragStartHit { in_drag_regions: true, ..Default::default() }.starts_drag());
+/// // Nothing starts a move while the position is locked.
+/// assert!(!DragStartHit {
+///     in_title_bar: true,
+///     in_drag_regions: true,
+///     movable_by_window_background: true,
+///     position_locked: true,
+/// }.starts_drag());
+/// ```
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct DragStartHit {
+    /// Whether the point is on the title bar of the child window.
+    pub in_title_bar: bool,
+    /// Whether the point is in the [`ChildWindowDragRegions`] of the child window.
+    pub in_drag_regions: bool,
+    /// [`Window::movable_by_window_background`] of the child window itself.
+    pub movable_by_window_background: bool,
+    /// Whether the position of the child window is locked by [`ChildWindowLock`].
+    pub position_locked: bool,
+}
+
+impl DragStartHit {
+    /// Returns true if the press starts moving the child window.
+    pub const fn starts_drag(&self) -> bool {
+        !self.position_locked && (self.in_title_bar || self.in_drag_regions || self.movable_by_window_background)
+    }
+
+    /// Returns true if the press starts moving the child window from the title bar or [`ChildWindowDragRegions`],
+    /// which is handled like the native title bar, unlike the rest of the background.
+    #[cfg_attr(not(any(test, target_os = "windows")), allow(dead_code))]
+    pub(crate) const fn starts_caption_drag(&self) -> bool {
+        !self.position_locked && (self.in_title_bar || self.in_drag_regions)
+    }
+}
+
+/// The drag settings of a child window shared with the native event handlers.
+pub(crate) struct DragHitTest {
+    regions: Option<ChildWindowDragRegions>,
+    scale_factor: f32,
+    movable_by_window_background: bool,
+}
+
+impl SharedState {
+    /// Returns whether pressing at `position` in logical pixels relative to the client area of the child window starts moving it.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub(crate) fn starts_drag(&self, entity: Entity, position: Vec2, in_title_bar: bool) -> bool {
+        self.drag_start_hit(entity, in_title_bar, |_| position).starts_drag()
+    }
+
+    /// Returns the state at `position` in physical pixels relative to the client area of the child window.
+    #[cfg_attr(not(any(test, target_os = "windows")), allow(dead_code))]
+    pub(crate) fn drag_start_hit_physical(&self, entity: Entity, position: Vec2) -> DragStartHit {
+        self.drag_start_hit(entity, false, |scale_factor| position / scale_factor)
+    }
+
+    fn drag_start_hit(&self, entity: Entity, in_title_bar: bool, logical_position: impl Fn(f32) -> Vec2) -> DragStartHit {
+        let title_bar_height = match self.window_settings(entity).title_bar_height {
+            TitleBarHeight::Native => 0.,
+            TitleBarHeight::Fixed(height) => height,
+        };
+        let (in_fixed_title_bar, in_drag_regions, movable_by_window_background) = self
+            .drag_hit_tests
+            .lock()
+            .unwrap()
+            .get(&entity)
+            .map(|hit_test| {
+                let position = logical_position(hit_test.scale_factor);
+                let in_drag_regions = hit_test
+                    .regions
+                    .as_ref()
+                    .is_some_and(|regions| regions.contains(position));
+                ((0. ..title_bar_height).contains(&position.y), in_drag_regions, hit_test.movable_by_window_background)
+            })
+            .unwrap_or_default();
+        DragStartHit {
+            in_title_bar: in_title_bar || in_fixed_title_bar,
+            in_drag_regions,
+            movable_by_window_background,
+            position_locked: self.window_lock(entity).position,
+        }
+    }
+}
+
+fn sync_drag_hit_tests(
+    mut removed_regions: RemovedComponents<ChildWindowDragRegions>,
+    mut removed_children: RemovedComponents<ParentWindow>,
+    windows: Query<
+        (Entity, &Window, Option<&ChildWindowDragRegions>),
+        (With<ParentWindow>, Or<(Added<ParentWindow>, Changed<ChildWindowDragRegions>, Changed<Window>)>),
+    >,
+    children: Query<(), With<ParentWindow>>,
+    backend: Res<ActiveBackend>,
+) {
+    let mut hit_tests = backend.shared.drag_hit_tests.lock().unwrap();
+    for entity in removed_regions.read() {
+        if let Some(hit_test) = hit_tests.get_mut(&entity) {
+            hit_test.regions = None;
+        }
+    }
+    // The child window may have been detached and attached again within the frame.
+    for entity in removed_children.read().filter(|entity| !children.contains(*entity)) {
+        hit_tests.remove(&entity);
+    }
+    for (entity, window, regions) in windows.iter() {
+        hit_tests.insert(entity, DragHitTest {
+            regions: regions.cloned(),
+            scale_factor: window.scale_factor(),
+            movable_by_window_background: window.movable_by_window_background,
+        });
+    }
+}
+
+fn take_drag_move_requests(
+    mut ew: EventWriter<StartChildWindowDrag>,
+    mut windows: Query<(Entity, &mut Window), (With<ParentWindow>, Changed<Window>)>,
+) {
+    for (entity, mut window) in windows.iter_mut() {
+        if window.bypass_change_detection().internal.take_move_request() {
+            ew.write(StartChildWindowDrag(entity));
+        }
+    }
+}
+
+fn start_drags(
+    mut er: EventReader<StartChildWindowDrag>,
+    backend: Res<ActiveBackend>,
+    winit_windows: NonSend<WinitWindows>,
+    windows: Query<(&ParentWindow, Option<&ChildWindowLock>), With<Window>>,
+) {
+    for StartChildWindowDrag(entity) in er.read() {
+        let Ok((ParentWindow(parent), lock)) = windows.get(*entity) else {
+            continue;
+        };
+        if !lock.is_some_and(|lock| lock.position) {
+            backend.start_drag(&winit_windows, *entity, *parent);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::{mock_app, MockBackend};
+    use crate::ChildWindowSettings;
+
+    const ENTITY: Entity = Entity::from_raw(1);
+
+    fn shared_state(title_bar_height: TitleBarHeight, hit_test: DragHitTest) -> SharedState {
+        let shared = SharedState::new(ChildWindowSettings {
+            title_bar_height,
+            ..Default::default()
+        });
+        shared.drag_hit_tests.lock().unwrap().insert(ENTITY, hit_test);
+        shared
+    }
+
+    fn hit_test(regions: Option<ChildWindowDragRegions>, scale_factor: f32, movable_by_window_background: bool) -> DragHitTest {
+        DragHitTest {
+            regions,
+            scale_factor,
+            movable_by_window_background,
+        }
+    }
+
+    #[test]
+    fn native_title_bar_starts_drag() {
+        let shared = shared_state(TitleBarHeight::Native, hit_test(None, 1., false));
+        assert!(shared.starts_drag(ENTITY, Vec2::new(10., -5.), true));
+        assert!(!shared.starts_drag(ENTITY, Vec2::new(10., 5.), false));
+    }
+
+    #[test]
+    fn fixed_title_bar_starts_caption_drag() {
+        let shared = shared_state(TitleBarHeight::Fixed(30.), hit_test(None, 1., false));
+        assert!(shared.drag_start_hit_physical(ENTITY, Vec2::new(10., 29.)).starts_caption_drag());
+        assert!(!shared.drag_start_hit_physical(ENTITY, Vec2::new(10., 30.)).starts_drag());
+    }
+
+    #[test]
+    fn drag_regions_start_caption_drag() {
+        let regions = ChildWindowDragRegions::new([Rect::new(100., 0., 200., 20.)]);
+        let shared = shared_state(TitleBarHeight::Native, hit_test(Some(regions), 1., false));
+        assert!(shared.drag_start_hit_physical(ENTITY, Vec2::new(150., 10.)).starts_caption_drag());
+        assert!(!shared.drag_start_hit_physical(ENTITY, Vec2::new(50., 10.)).starts_drag());
+    }
+
+    #[test]
+    fn background_starts_drag_but_not_caption_drag() {
+        let shared = shared_state(TitleBarHeight::Native, hit_test(None, 1., true));
+        let hit = shared.drag_start_hit_physical(ENTITY, Vec2::new(50., 50.));
+        assert!(hit.starts_drag());
+        assert!(!hit.starts_caption_drag());
+    }
+
+    #[test]
+    fn physical_position_is_scaled() {
+        let regions = ChildWindowDragRegions::new([Rect::new(0., 0., 100., 20.)]);
+        let shared = shared_state(TitleBarHeight::Fixed(10.), hit_test(Some(regions), 2., false));
+        let hit = shared.drag_start_hit_physical(ENTITY, Vec2::new(150., 30.));
+        assert!(hit.in_drag_regions);
+        assert!(!hit.in_title_bar);
+        assert!(shared.drag_start_hit_physical(ENTITY, Vec2::new(150., 18.)).in_title_bar);
+        assert!(!shared.drag_start_hit_physical(ENTITY, Vec2::new(250., 30.)).starts_drag());
+    }
+
+    #[test]
+    fn position_lock_prevents_drag() {
+        let regions = ChildWindowDragRegions::new([Rect::new(0., 0., 100., 20.)]);
+        let shared = shared_state(TitleBarHeight::Fixed(30.), hit_test(Some(regions), 1., true));
+        shared.locks.lock().unwrap().insert(ENTITY, ChildWindowLock::POSITION);
+        let hit = shared.drag_start_hit_physical(ENTITY, Vec2::new(10., 10.));
+        assert!(hit.position_locked);
+        assert!(!hit.starts_drag());
+        assert!(!hit.starts_caption_drag());
+        assert!(!shared.starts_drag(ENTITY, Vec2::new(10., -5.), true));
+
+        shared.locks.lock().unwrap().insert(ENTITY, ChildWindowLock::SIZE);
+        assert!(shared.drag_start_hit_physical(ENTITY, Vec2::new(10., 10.)).starts_caption_drag());
+    }
+
+    #[test]
+    fn unknown_window_does_not_start_drag() {
+        let shared = SharedState::new(ChildWindowSettings::default());
+        assert!(!shared.drag_start_hit_physical(ENTITY, Vec2::new(10., 10.)).starts_drag());
+    }
+
+    #[test]
+    fn reparented_child_window_keeps_drag_regions() {
+        let mut app = mock_app(Arc::new(MockBackend::default()));
+        let parent = app.world_mut().spawn(Window::default()).id();
+        let other_parent = app.world_mut().spawn(Window::default()).id();
+        let child = app
+            .world_mut()
+            .spawn((
+                Window::default(),
+                ParentWindow(parent),
+                ChildWindowDragRegions::new([Rect::new(0., 0., 100., 20.)]),
+            ))
+            .id();
+        app.update();
+        app.world_mut()
+            .entity_mut(child)
+            .remove::<ParentWindow>()
+            .insert(ParentWindow(other_parent));
+        app.update();
+        app.update();
+
+        let backend = app.world().resource::<ActiveBackend>();
+        assert!(backend.shared.drag_start_hit_physical(child, Vec2::new(10., 10.)).starts_caption_drag());
+    }
+}
@@ -0,0 +1,77 @@
+use bevy::prelude::{Entity, Event};
+use std::fmt::{Display, Formatter};
+
+/// Fired when a child window cannot be attached to its parent window.
+///
+/// [`ParentWindow`](crate::ParentWindow) is validated when it is inserted or changed.
+/// If it is invalid, it is removed so that the window stays a top-level window.
+#[derive(Event, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ChildWindowError {
+    /// The child window is specified as its own parent window.
+    SelfParent {
+        /// The entity of the child window.
+        window: Entity,
+    },
+    /// The entity specified as the parent window does not exist.
+    ParentNotFound {
+        /// The entity of the child window.
+        window: Entity,
+        /// The entity specified as the parent window.
+        parent: Entity,
+    },
+    /// The entity specified as the parent window has no [`Window`](bevy::window::Window).
+    ParentNotWindow {
+        /// The entity of the child window.
+        window: Entity,
+        /// The entity specified as the parent window.
+        parent: Entity,
+    },
+    /// The parent window is a descendant of the child window, so the [`ParentWindow`](crate::ParentWindow) forms a loop.
+    ParentCycle {
+        /// The entity of the child window.
+        window: Entity,
+        /// The entity specified as the parent window.
+        parent: Entity,
+    },
+    /// The child window could not be attached to its parent window within [`AttachRetryPolicy::Frames`](crate::AttachRetryPolicy::Frames).
+    AttachFailed {
+        /// The entity of the child window.
+        window: Entity,
+        /// The entity of the parent window.
+        parent: Entity,
+    },
+    /// [`ChildWindowPlugin`](crate::ChildWindowPlugin) is not added to the app.
+    ///
+    /// This is only logged because the event itself is registered by the plugin.
+    MissingPlugin {
+        /// The entity of the child window.
+        window: Entity,
+    },
+}
+
+impl Display for ChildWindowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SelfParent { window } => {
+                write!(f, "The window {window} cannot be its own parent window")
+            }
+            Self::ParentNotFound { window, parent } => {
+                write!(f, "The child window {window} cannot be attached to {parent} because it does not exist")
+            }
+            Self::ParentNotWindow { window, parent } => {
+                write!(f, "The child window {window} cannot be attached to {parent} because it has no `Window`")
+            }
+            Self::ParentCycle { window, parent } => {
+                write!(f, "The child window {window} cannot be attached to {parent} because {parent} is a descendant of it")
+            }
+            Self::AttachFailed { window, parent } => {
+                write!(f, "The child window {window} could not be attached to {parent} and gave up retrying")
+            }
+            Self::MissingPlugin { window } => {
+                write!(f, "`ParentWindow` was inserted into {window}, but `ChildWindowPlugin` is not added")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChildWindowError {}
@@ -0,0 +1,392 @@
+use crate::backend::ActiveBackend;
+use crate::util::retain_events;
+use crate::{ChildWindowMinimized, ModalBlockedWindows, ParentWindow};
+use bevy::app::{App, First, Plugin, PreUpdate};
+use bevy::ecs::event::{EventCursor, EventUpdates};
+use bevy::input::keyboard::KeyCode;
+use bevy::input::{ButtonInput, InputSystem};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::{Entity, Event, EventReader, EventWriter, Events, IntoScheduleConfigs, Local, NonSend, Query, Reflect, ReflectResource, RemovedComponents, Res, ResMut, Resource, With, Without};
+use bevy::window::{Window, WindowFocused};
+use bevy::winit::WinitWindows;
+
+pub(crate) struct ChildWindowFocusPlugin;
+
+impl Plugin for ChildWindowFocusPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<ChildWindowFocusCycling>()
+            .init_resource::<FocusedChildWindow>()
+            .init_resource::<ChildWindowFocusCycling>()
+            .add_event::<FocusChildWindow>()
+            .add_event::<ChildWindowFocusChanged>()
+            .add_systems(First, normalize_window_focused_events.after(EventUpdates))
+            .add_systems(PreUpdate, (
+                track_native_focus,
+                cycle_focus.run_if(|cycling: Res<ChildWindowFocusCycling>| cycling.enabled),
+                focus_child_windows,
+                forget_removed_child_windows,
+            )
+                .chain()
+                .after(InputSystem));
+    }
+}
+
+/// Holds the focused child window of each parent window.
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct FocusedChildWindow(HashMap<Entity, Entity>);
+
+impl FocusedChildWindow {
+    /// Returns the focused child window of `parent`.
+    pub fn get(&self, parent: Entity) -> Option<Entity> {
+        self.0.get(&parent).copied()
+    }
+
+    /// Returns true if `child` is the focused child window of its parent.
+    pub fn is_focused(&self, child: Entity) -> bool {
+        self.0.values().any(|focused| *focused == child)
+    }
+}
+
+/// Raises and focuses the child window.
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_child_window::prelude::*;
+///
+/// fn focus(
+///     mut ew: EventWriter<FocusChildWindow>,
+///     child_windows: Query<Entity, With<ParentWindow>>,
+/// ){
+///     if let Some(entity) = child_windows.iter().next() {
+///         ew.write(FocusChildWindow(entity));
+///     }
+/// }
+/// ```
+#[derive(Event, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct FocusChildWindow(pub Entity);
+
+/// Fired when the focused child window of a parent window changes.
+///
+/// This is fired both when the focus is changed by [`FocusChildWindow`] and when the user clicks on a window.
+/// Focusing the parent window itself blurs its focused child window, which is reported with [`ChildWindowFocusChanged::focused`] set to `None`.
+///
+/// ## Relation to [`WindowFocused`]
+///
+/// A parent window is treated as focused while either itself or any of its child windows is focused.
+/// Therefore, [`WindowFocused`] events are normalized as follows:
+///
+/// - When the focus moves from a parent window to its child window, the parent's `WindowFocused { focused: false }` is not reported.
+/// - When the focus moves from a child window back to its parent window, the parent's `WindowFocused { focused: true }` is not reported.
+/// - When the focus moves between sibling child windows, both the blur and the focus of the children are reported.
+/// - When the focus moves from a child window to a window outside its parent window, the parent's `WindowFocused { focused: false }` is also reported.
+///
+/// Note that [`Window::focused`] still reflects whether the native window is focused.
+#[derive(Event, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ChildWindowFocusChanged {
+    /// The entity of the parent window.
+    pub parent: Entity,
+    /// The child window that lost the focus.
+    pub previous: Option<Entity>,
+    /// The child window that gained the focus.
+    pub focused: Option<Entity>,
+}
+
+/// The keyboard binding to cycle the focus between the child windows of the focused parent window.
+///
+/// It is disabled by default.
+/// When enabled, pressing [`ChildWindowFocusCycling::key`] while holding any of [`ChildWindowFocusCycling::modifiers`]
+/// raises and focuses the next child window, and additionally holding any of [`ChildWindowFocusCycling::reverse_modifiers`]
+/// focuses the previous one.
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ChildWindowFocusCycling {
+    /// Whether the keyboard cycling is enabled.
+    pub enabled: bool,
+    /// The key that cycles the focus.
+    pub key: KeyCode,
+    /// Any of these keys must be held to cycle the focus.
+    ///
+    /// If empty, [`ChildWindowFocusCycling::key`] alone cycles the focus.
+    pub modifiers: Vec<KeyCode>,
+    /// Holding any of these keys cycles the focus in reverse order.
+    pub reverse_modifiers: Vec<KeyCode>,
+}
+
+impl Default for ChildWindowFocusCycling {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key: KeyCode::Tab,
+            modifiers: vec![KeyCode::ControlLeft, KeyCode::ControlRight],
+            reverse_modifiers: vec![KeyCode::ShiftLeft, KeyCode::ShiftRight],
+        }
+    }
+}
+
+fn cycle_focus(
+    mut ew: EventWriter<FocusChildWindow>,
+    cycling: Res<ChildWindowFocusCycling>,
+    focused_child_window: Res<FocusedChildWindow>,
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    windows: Query<(Entity, &Window, Option<&ParentWindow>)>,
+    children: Query<(Entity, &ParentWindow), (With<Window>, Without<ChildWindowMinimized>)>,
+) {
+    let Some(keys) = keys else {
+        return;
+    };
+    if !keys.just_pressed(cycling.key) || (!cycling.modifiers.is_empty() && !keys.any_pressed(cycling.modifiers.iter().copied())) {
+        return;
+    }
+    let Some(parent) = windows
+        .iter()
+        .find(|(_, window, _)| window.focused)
+        .map(|(entity, _, parent)| parent.map(|p| p.0).unwrap_or(entity)) else {
+        return;
+    };
+    let mut siblings = children
+        .iter()
+        .filter(|(_, p)| p.0 == parent)
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    if siblings.is_empty() {
+        return;
+    }
+    siblings.sort();
+    let reverse = keys.any_pressed(cycling.reverse_modifiers.iter().copied());
+    let next = match focused_child_window.get(parent).and_then(|focused| siblings.iter().position(|e| *e == focused)) {
+        Some(i) if reverse => (i + siblings.len() - 1) % siblings.len(),
+        Some(i) => (i + 1) % siblings.len(),
+        None if reverse => siblings.len() - 1,
+        None => 0,
+    };
+    ew.write(FocusChildWindow(siblings[next]));
+}
+
+fn focus_child_windows(
+    mut er: EventReader<FocusChildWindow>,
+    mut ew: EventWriter<ChildWindowFocusChanged>,
+    mut focused_child_window: ResMut<FocusedChildWindow>,
+    windows: Query<&ParentWindow, With<Window>>,
+    blocked_windows: Res<ModalBlockedWindows>,
+    backend: Res<ActiveBackend>,
+    winit_windows: NonSend<WinitWindows>,
+) {
+    for FocusChildWindow(entity) in er.read() {
+        let entity = blocked_windows.modal_of(*entity).unwrap_or(*entity);
+        let Ok(ParentWindow(parent)) = windows.get(entity) else {
+            continue;
+        };
+        backend.focus(&winit_windows, entity);
+        let previous = focused_child_window.0.insert(*parent, entity);
+        if previous != Some(entity) {
+            ew.write(ChildWindowFocusChanged {
+                parent: *parent,
+                previous,
+                focused: Some(entity),
+            });
+        }
+    }
+}
+
+fn forget_removed_child_windows(
+    mut removed: RemovedComponents<ParentWindow>,
+    mut ew: EventWriter<ChildWindowFocusChanged>,
+    mut focused_child_window: ResMut<FocusedChildWindow>,
+) {
+    for entity in removed.read() {
+        let Some(parent) = focused_child_window.0.iter().find(|(_, child)| **child == entity).map(|(parent, _)| *parent) else {
+            continue;
+        };
+        focused_child_window.0.remove(&parent);
+        ew.write(ChildWindowFocusChanged {
+            parent,
+            previous: Some(entity),
+            focused: None,
+        });
+    }
+}
+
+fn normalize_window_focused_events(
+    mut cursor: Local<EventCursor<WindowFocused>>,
+    events: Option<ResMut<Events<WindowFocused>>>,
+    children: Query<&ParentWindow, With<Window>>,
+) {
+    let Some(mut events) = events else {
+        return;
+    };
+    let parent_of = |window: Entity| children.get(window).ok().map(|p| p.0);
+    let batch = cursor.clone().read(&events).cloned().collect::<Vec<_>>();
+    let mut focused_parents = Vec::new();
+    let mut blurred_parents = Vec::new();
+    for e in batch.iter() {
+        let Some(parent) = parent_of(e.window) else {
+            continue;
+        };
+        if e.focused {
+            focused_parents.push(parent);
+        } else {
+            blurred_parents.push(parent);
+        }
+    }
+    events.send_batch(parent_blurs(&batch, parent_of));
+    retain_events(&mut events, &mut cursor, |e| {
+        if e.focused {
+            !blurred_parents.contains(&e.window)
+        } else {
+            !focused_parents.contains(&e.window)
+        }
+    });
+}
+
+/// Returns the blurs of the parent windows that lose the focus together with their child window.
+///
+/// The blur of a parent window is not reported when the focus moves to its child window,
+/// so it is reported when the focus leaves the child window for a window outside the parent window instead.
+fn parent_blurs(
+    batch: &[WindowFocused],
+    parent_of: impl Fn(Entity) -> Option<Entity>,
+) -> Vec<WindowFocused> {
+    let is_within = |window: Entity, ancestor: Entity| {
+        let mut visited = HashSet::new();
+        let mut current = Some(window);
+        while let Some(w) = current {
+            if w == ancestor {
+                return true;
+            }
+            if !visited.insert(w) {
+                return false;
+            }
+            current = parent_of(w);
+        }
+        false
+    };
+    let mut blurs = Vec::<WindowFocused>::new();
+    for e in batch.iter().filter(|e| !e.focused) {
+        let mut window = e.window;
+        while let Some(parent) = parent_of(window) {
+            let keeps_focus = batch.iter().any(|f| f.focused && is_within(f.window, parent));
+            let blurred = batch.iter().chain(blurs.iter()).any(|b| !b.focused && b.window == parent);
+            if keeps_focus || blurred {
+                break;
+            }
+            blurs.push(WindowFocused {
+                window: parent,
+                focused: false,
+            });
+            window = parent;
+        }
+    }
+    blurs
+}
+
+fn track_native_focus(
+    mut er: EventReader<WindowFocused>,
+    mut ew: EventWriter<ChildWindowFocusChanged>,
+    mut focused_child_window: ResMut<FocusedChildWindow>,
+    children: Query<&ParentWindow, With<Window>>,
+) {
+    let mut changes = HashMap::<Entity, Option<Entity>>::default();
+    for WindowFocused { window, focused } in er.read() {
+        if let Ok(ParentWindow(parent)) = children.get(*window) {
+            if *focused {
+                changes.insert(*parent, Some(*window));
+            } else if focused_child_window.get(*parent) == Some(*window) {
+                changes.entry(*parent).or_insert(None);
+            }
+        } else if *focused && focused_child_window.get(*window).is_some() {
+            changes.entry(*window).or_insert(None);
+        }
+    }
+    for (parent, focused) in changes {
+        let previous = match focused {
+            Some(child) => focused_child_window.0.insert(parent, child),
+            None => focused_child_window.0.remove(&parent),
+        };
+        if previous != focused {
+            ew.write(ChildWindowFocusChanged {
+                parent,
+                previous,
+                focused,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Windows {
+        app: App,
+        parent: Entity,
+        child: Entity,
+        sibling: Entity,
+        other: Entity,
+    }
+
+    fn setup() -> Windows {
+        let mut app = App::new();
+        app
+            .add_event::<WindowFocused>()
+            .add_systems(First, normalize_window_focused_events.after(EventUpdates));
+        let world = app.world_mut();
+        let parent = world.spawn(Window::default()).id();
+        let child = world.spawn((Window::default(), ParentWindow(parent))).id();
+        let sibling = world.spawn((Window::default(), ParentWindow(parent))).id();
+        let other = world.spawn(Window::default()).id();
+        Windows {
+            app,
+            parent,
+            child,
+            sibling,
+            other,
+        }
+    }
+
+    /// Sends the native focus events and returns the normalized ones.
+    fn update(app: &mut App, sent: &[(Entity, bool)]) -> Vec<(Entity, bool)> {
+        let mut cursor = app.world().resource::<Events<WindowFocused>>().get_cursor_current();
+        app.world_mut().send_event_batch(sent.iter().map(|(window, focused)| WindowFocused {
+            window: *window,
+            focused: *focused,
+        }));
+        app.update();
+        let events = app.world().resource::<Events<WindowFocused>>();
+        cursor.read(events).map(|e| (e.window, e.focused)).collect()
+    }
+
+    #[test]
+    fn parent_to_child_to_other_window_blurs_parent() {
+        let Windows { mut app, parent, child, other, .. } = setup();
+        assert_eq!(update(&mut app, &[(other, false), (parent, true)]), vec![(other, false), (parent, true)]);
+        assert_eq!(update(&mut app, &[(parent, false), (child, true)]), vec![(child, true)]);
+        assert_eq!(update(&mut app, &[(child, false), (other, true)]), vec![(child, false), (other, true), (parent, false)]);
+    }
+
+    #[test]
+    fn child_to_parent_does_not_blur_parent() {
+        let Windows { mut app, parent, child, .. } = setup();
+        update(&mut app, &[(parent, false), (child, true)]);
+        assert_eq!(update(&mut app, &[(child, false), (parent, true)]), vec![(child, false)]);
+    }
+
+    #[test]
+    fn child_to_sibling_does_not_blur_parent() {
+        let Windows { mut app, parent, child, sibling, .. } = setup();
+        update(&mut app, &[(parent, false), (child, true)]);
+        assert_eq!(update(&mut app, &[(child, false), (sibling, true)]), vec![(child, false), (sibling, true)]);
+    }
+
+    #[test]
+    fn nested_child_to_other_window_blurs_ancestors() {
+        let Windows { mut app, parent, child, other, .. } = setup();
+        let grandchild = app.world_mut().spawn((Window::default(), ParentWindow(child))).id();
+        update(&mut app, &[(parent, false), (grandchild, true)]);
+        assert_eq!(
+            update(&mut app, &[(grandchild, false), (other, true)]),
+            vec![(grandchild, false), (other, true), (child, false), (parent, false)],
+        );
+    }
+}
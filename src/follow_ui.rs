@@ -0,0 +1,225 @@
+use crate::backend::ActiveBackend;
+use crate::{ChildWindowSystems, ParentWindow, UnInitializeWindow};
+use bevy::app::{App, Plugin, PostUpdate};
+use bevy::math::{Rect, Vec2};
+use bevy::platform::collections::HashMap;
+use bevy::ecs::entity::ContainsEntity;
+use bevy::prelude::{Camera, Commands, Component, Entity, GlobalTransform, InheritedVisibility, IntoScheduleConfigs, NonSend, ParamSet, Query, Reflect, ReflectComponent, TransformSystem, Res, Window, With, Without};
+use bevy::render::camera::NormalizedRenderTarget;
+use bevy::render::view::VisibilitySystems;
+use bevy::ui::{CalculatedClip, ComputedNode, ComputedNodeTarget, UiSystem};
+use bevy::window::PrimaryWindow;
+use bevy::winit::WinitWindows;
+
+pub(crate) struct ChildWindowFollowUiPlugin;
+
+impl Plugin for ChildWindowFollowUiPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<FollowUiNode>()
+            .add_systems(PostUpdate, follow_ui_nodes
+                .after(UiSystem::PostLayout)
+                .after(TransformSystem::TransformPropagate)
+                .after(VisibilitySystems::VisibilityPropagate)
+                .in_set(ChildWindowSystems::Layout),
+            );
+    }
+}
+
+/// Keeps the child window over the rect of the UI node in its parent window.
+///
+/// The position and the size of the client area of the child window follow the computed layout of the node.
+/// The child window is hidden while the node is hidden or entirely clipped, and if the node is partly clipped, only the visible part is covered.
+/// It is also hidden while the camera of the node doesn't render to the parent window.
+///
+/// This requires the `bevy_ui` feature.
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::window::PrimaryWindow;
+/// use bevy_child_window::prelude::*;
+///
+/// fn spawn_child_window(
+///     mut commands: Commands,
+///     parent: Query<Entity, With<PrimaryWindow>>,
+/// ){
+///     let node = commands.spawn(Node {
+///         width: Val::Px(300.),
+///         height: Val::Px(200.),
+///         ..default()
+///     }).id();
+///     commands.spawn((
+///         ParentWindow(parent.single().expect("Parent not found")),
+///         FollowUiNode(node),
+///         Window {
+///             decorations: false,
+///             ..default()
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Copy, Clone, Eq, PartialEq)]
+#[reflect(Component)]
+pub struct FollowUiNode(pub Entity);
+
+/// The state of the child window applied by [`FollowUiNode`].
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+enum FollowedUiNode {
+    /// The rect of the child window and the size of the parent window when it was applied.
+    Shown(Rect, Vec2),
+    Hidden,
+}
+
+fn follow_ui_nodes(
+    mut commands: Commands,
+    mut windows: ParamSet<(
+        Query<(Entity, &Window)>,
+        Query<(Entity, &FollowUiNode, &ParentWindow, &mut Window, Option<&FollowedUiNode>), Without<UnInitializeWindow>>,
+    )>,
+    nodes: Query<(&ComputedNode, &GlobalTransform, Option<&InheritedVisibility>, Option<&CalculatedClip>, Option<&ComputedNodeTarget>)>,
+    cameras: Query<&Camera>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    backend: Res<ActiveBackend>,
+    winit_windows: NonSend<WinitWindows>,
+) {
+    // The rect is re-applied when the parent window is resized, since the native origin may not be the top-left corner.
+    let window_sizes = windows
+        .p0()
+        .iter()
+        .map(|(entity, window)| (entity, window.size()))
+        .collect::<HashMap<_, _>>();
+    for (entity, FollowUiNode(node), ParentWindow(parent), mut window, followed) in windows.p1().iter_mut() {
+        let Ok((computed, transform, visibility, clip, target)) = nodes.get(*node) else {
+            continue;
+        };
+        let Some(parent_size) = window_sizes.get(parent).copied() else {
+            continue;
+        };
+        let rendered_to_parent = target
+            .and_then(ComputedNodeTarget::camera)
+            .and_then(|camera| cameras.get(camera).ok())
+            .is_none_or(|camera| renders_to(camera, *parent, primary_window.single().ok()));
+        let state = visible_rect(computed, transform, visibility, clip)
+            .filter(|_| rendered_to_parent)
+            .map(|rect| FollowedUiNode::Shown(rect, parent_size))
+            .unwrap_or(FollowedUiNode::Hidden);
+        if followed == Some(&state) {
+            continue;
+        }
+        match state {
+            FollowedUiNode::Shown(rect, _) => {
+                backend.set_rect(&winit_windows, entity, *parent, rect);
+                if followed == Some(&FollowedUiNode::Hidden) {
+                    window.visible = true;
+                }
+            }
+            FollowedUiNode::Hidden => {
+                window.visible = false;
+            }
+        }
+        commands.entity(entity).insert(state);
+    }
+}
+
+/// Returns true if `camera` renders to `window`.
+fn renders_to(camera: &Camera, window: Entity, primary_window: Option<Entity>) -> bool {
+    matches!(
+        camera.target.normalize(primary_window),
+        Some(NormalizedRenderTarget::Window(target)) if target.entity() == window
+    )
+}
+
+/// Returns the visible rect of the node in logical pixels, or `None` if it is not visible.
+fn visible_rect(
+    computed: &ComputedNode,
+    transform: &GlobalTransform,
+    visibility: Option<&InheritedVisibility>,
+    clip: Option<&CalculatedClip>,
+) -> Option<Rect> {
+    if visibility.is_some_and(|v| !v.get()) || computed.is_empty() {
+        return None;
+    }
+    let rect = Rect::from_center_size(transform.translation().truncate(), computed.size());
+    let rect = match clip {
+        Some(clip) => rect.intersect(clip.clip),
+        None => rect,
+    };
+    if rect.is_empty() {
+        return None;
+    }
+    let inverse_scale_factor = computed.inverse_scale_factor();
+    Some(Rect::from_corners(rect.min * inverse_scale_factor, rect.max * inverse_scale_factor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::Vec3;
+    use bevy::render::camera::RenderTarget;
+    use bevy::window::WindowRef;
+
+    fn node(size: Vec2, inverse_scale_factor: f32) -> ComputedNode {
+        ComputedNode {
+            size,
+            inverse_scale_factor,
+            ..ComputedNode::DEFAULT
+        }
+    }
+
+    #[test]
+    fn rect_of_node_in_logical_pixels() {
+        let transform = GlobalTransform::from_translation(Vec3::new(300., 200., 0.));
+        assert_eq!(
+            visible_rect(&node(Vec2::new(200., 100.), 1.), &transform, None, None),
+            Some(Rect::new(200., 150., 400., 250.)),
+        );
+        assert_eq!(
+            visible_rect(&node(Vec2::new(200., 100.), 0.5), &transform, None, None),
+            Some(Rect::new(100., 75., 200., 125.)),
+        );
+    }
+
+    #[test]
+    fn clipped_part_of_node_is_excluded() {
+        let transform = GlobalTransform::from_translation(Vec3::new(300., 200., 0.));
+        let clip = CalculatedClip {
+            clip: Rect::new(0., 0., 300., 180.),
+        };
+        assert_eq!(
+            visible_rect(&node(Vec2::new(200., 100.), 0.5), &transform, None, Some(&clip)),
+            Some(Rect::new(100., 75., 150., 90.)),
+        );
+
+        let clip = CalculatedClip {
+            clip: Rect::new(0., 0., 100., 100.),
+        };
+        assert_eq!(visible_rect(&node(Vec2::new(200., 100.), 1.), &transform, None, Some(&clip)), None);
+    }
+
+    #[test]
+    fn hidden_or_empty_node_has_no_rect() {
+        let transform = GlobalTransform::from_translation(Vec3::new(300., 200., 0.));
+        assert_eq!(visible_rect(&node(Vec2::ZERO, 1.), &transform, None, None), None);
+        assert_eq!(
+            visible_rect(&node(Vec2::new(200., 100.), 1.), &transform, Some(&InheritedVisibility::HIDDEN), None),
+            None,
+        );
+    }
+
+    #[test]
+    fn camera_renders_to_parent_window() {
+        let primary = Entity::from_raw(1);
+        let other = Entity::from_raw(2);
+        let camera = Camera::default();
+        assert!(renders_to(&camera, primary, Some(primary)));
+        assert!(!renders_to(&camera, other, Some(primary)));
+
+        let camera = Camera {
+            target: RenderTarget::Window(WindowRef::Entity(other)),
+            ..Camera::default()
+        };
+        assert!(renders_to(&camera, other, Some(primary)));
+        assert!(!renders_to(&camera, primary, Some(primary)));
+    }
+}
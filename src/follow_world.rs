@@ -0,0 +1,196 @@
+use crate::backend::ActiveBackend;
+use crate::{ChildWindowSystems, ParentWindow, UnInitializeWindow};
+use bevy::app::{App, Plugin, PostUpdate};
+use bevy::math::{Rect, Vec2};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Camera, Commands, Component, Entity, GlobalTransform, IntoScheduleConfigs, NonSend, ParamSet, Query, Reflect, ReflectComponent, ReflectDefault, TransformSystem, Res, Window, Without};
+use bevy::render::camera::CameraUpdateSystem;
+use bevy::winit::WinitWindows;
+
+pub(crate) struct ChildWindowFollowWorldPlugin;
+
+impl Plugin for ChildWindowFollowWorldPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<FollowWorldEntity>()
+            .register_type::<OffScreenPolicy>()
+            .add_systems(PostUpdate, follow_world_entities
+                .after(TransformSystem::TransformPropagate)
+                .after(CameraUpdateSystem)
+                .in_set(ChildWindowSystems::Layout),
+            );
+    }
+}
+
+/// Keeps the child window hovering over an entity in the world.
+///
+/// Each frame, the [`GlobalTransform`] of [`FollowWorldEntity::target`] is projected through [`FollowWorldEntity::camera`],
+/// and the top-left corner of the client area of the child window is moved to the projected point plus [`FollowWorldEntity::offset`].
+/// The camera is expected to render to the parent window.
+///
+/// This requires the `bevy_render` feature.
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::window::PrimaryWindow;
+/// use bevy_child_window::prelude::*;
+///
+/// fn spawn_inspector(
+///     mut commands: Commands,
+///     parent: Query<Entity, With<PrimaryWindow>>,
+///     camera: Query<Entity, With<Camera>>,
+///     target: Query<Entity, With<Mesh3d>>,
+/// ){
+///     commands.spawn((
+///         ParentWindow(parent.single().expect("Parent not found")),
+///         FollowWorldEntity {
+///             offset: Vec2::new(20., -20.),
+///             off_screen: OffScreenPolicy::ClampToEdge,
+///             ..FollowWorldEntity::new(target.single().unwrap(), camera.single().unwrap())
+///         },
+///         Window {
+///             decorations: false,
+///             ..default()
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Copy, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct FollowWorldEntity {
+    /// The entity to follow.
+    pub target: Entity,
+    /// The camera used to project the target into the parent window.
+    pub camera: Entity,
+    /// The offset of the top-left corner of the client area of the child window from the projected point in logical pixels.
+    pub offset: Vec2,
+    /// What happens when the target is out of the parent window.
+    pub off_screen: OffScreenPolicy,
+}
+
+impl FollowWorldEntity {
+    /// Follows `target` through `camera` without offset.
+    pub const fn new(target: Entity, camera: Entity) -> Self {
+        Self {
+            target,
+            camera,
+            offset: Vec2::ZERO,
+            off_screen: OffScreenPolicy::Hide,
+        }
+    }
+}
+
+/// The behavior of [`FollowWorldEntity`] when the target is out of the parent window.
+///
+/// Whether the target is out of the parent window is decided by its projected point, not by the rect of the child window.
+/// While shown, the child window is kept inside the client area of the parent window with either policy.
+#[derive(Reflect, Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[reflect(Default)]
+pub enum OffScreenPolicy {
+    /// The child window is hidden until the projected point of the target comes back into the parent window.
+    #[default]
+    Hide,
+    /// The child window is kept at the nearest edge of the parent window.
+    ///
+    /// The child window is still hidden while the target is behind the camera.
+    ClampToEdge,
+}
+
+/// The state of the child window applied by [`FollowWorldEntity`].
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+enum FollowedWorldEntity {
+    Shown(Rect),
+    Hidden,
+}
+
+fn follow_world_entities(
+    mut commands: Commands,
+    mut windows: ParamSet<(
+        Query<(Entity, &Window)>,
+        Query<(Entity, &FollowWorldEntity, &ParentWindow, &mut Window, Option<&FollowedWorldEntity>), Without<UnInitializeWindow>>,
+    )>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    targets: Query<&GlobalTransform>,
+    backend: Res<ActiveBackend>,
+    winit_windows: NonSend<WinitWindows>,
+) {
+    let window_sizes = windows
+        .p0()
+        .iter()
+        .map(|(entity, window)| (entity, window.size()))
+        .collect::<HashMap<_, _>>();
+    for (entity, follow, ParentWindow(parent), mut window, followed) in windows.p1().iter_mut() {
+        let (Ok((camera, camera_transform)), Ok(target)) = (cameras.get(follow.camera), targets.get(follow.target)) else {
+            continue;
+        };
+        let Some(parent_size) = window_sizes.get(parent).copied() else {
+            continue;
+        };
+        let state = camera
+            .world_to_viewport(camera_transform, target.translation())
+            .ok()
+            // The size is the client area the same as the rect of `set_rect`, so following the target doesn't change it.
+            .and_then(|point| follow_rect(point, follow.offset, window.size(), parent_size, follow.off_screen))
+            .map(FollowedWorldEntity::Shown)
+            .unwrap_or(FollowedWorldEntity::Hidden);
+        if followed == Some(&state) {
+            continue;
+        }
+        match state {
+            FollowedWorldEntity::Shown(rect) => {
+                backend.set_rect(&winit_windows, entity, *parent, rect);
+                if followed == Some(&FollowedWorldEntity::Hidden) {
+                    window.visible = true;
+                }
+            }
+            FollowedWorldEntity::Hidden => {
+                window.visible = false;
+            }
+        }
+        commands.entity(entity).insert(state);
+    }
+}
+
+/// Returns the rect of the child window of `size` following the projected `point` in the client area of the parent window of `parent_size`,
+/// or `None` if it is hidden by [`OffScreenPolicy`].
+fn follow_rect(point: Vec2, offset: Vec2, size: Vec2, parent_size: Vec2, policy: OffScreenPolicy) -> Option<Rect> {
+    let client_area = Rect::from_corners(Vec2::ZERO, parent_size);
+    if policy == OffScreenPolicy::Hide && !client_area.contains(point) {
+        return None;
+    }
+    let min = (point + offset).min(parent_size - size).max(Vec2::ZERO);
+    Some(Rect::from_corners(min, min + size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: Vec2 = Vec2::new(100., 50.);
+    const PARENT_SIZE: Vec2 = Vec2::new(800., 600.);
+
+    #[test]
+    fn hide_keeps_child_window_shown_while_point_is_visible() {
+        let rect = follow_rect(Vec2::new(780., 300.), Vec2::ZERO, SIZE, PARENT_SIZE, OffScreenPolicy::Hide);
+        assert_eq!(rect, Some(Rect::new(700., 300., 800., 350.)));
+    }
+
+    #[test]
+    fn hide_hides_child_window_when_point_is_out() {
+        let rect = follow_rect(Vec2::new(810., 300.), Vec2::new(-200., 0.), SIZE, PARENT_SIZE, OffScreenPolicy::Hide);
+        assert_eq!(rect, None);
+    }
+
+    #[test]
+    fn offset_places_child_window_from_point() {
+        let rect = follow_rect(Vec2::new(400., 300.), Vec2::new(20., -20.), SIZE, PARENT_SIZE, OffScreenPolicy::Hide);
+        assert_eq!(rect, Some(Rect::new(420., 280., 520., 330.)));
+    }
+
+    #[test]
+    fn clamp_to_edge_keeps_child_window_at_edge() {
+        let rect = follow_rect(Vec2::new(-50., 700.), Vec2::ZERO, SIZE, PARENT_SIZE, OffScreenPolicy::ClampToEdge);
+        assert_eq!(rect, Some(Rect::new(0., 550., 100., 600.)));
+    }
+}
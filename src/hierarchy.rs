@@ -0,0 +1,217 @@
+use crate::settings::log_level;
+use crate::util::log_error;
+use crate::{ChildWindowError, ChildWindowSettings, ParentWindow, UnInitializeWindow};
+use bevy::app::{App, Plugin, PreUpdate};
+use bevy::ecs::component::HookContext;
+use bevy::ecs::world::DeferredWorld;
+use bevy::platform::collections::HashSet;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Changed, Commands, Entity, EventWriter, Has, Query, Res, Resource};
+use bevy::window::Window;
+
+pub(crate) struct ChildWindowHierarchyPlugin;
+
+impl Plugin for ChildWindowHierarchyPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ChildWindowPluginAdded>()
+            .init_resource::<ReplacedParentWindows>()
+            .add_event::<ChildWindowError>()
+            .add_systems(PreUpdate, validate_parent_windows);
+    }
+}
+
+/// Marks that [`ChildWindowPlugin`](crate::ChildWindowPlugin) is added.
+#[derive(Resource, Default)]
+struct ChildWindowPluginAdded;
+
+/// The previous parent windows of the child windows whose [`ParentWindow`] is being replaced.
+#[derive(Resource, Default)]
+struct ReplacedParentWindows(HashMap<Entity, Entity>);
+
+pub(crate) fn on_add_parent_window(mut world: DeferredWorld, context: HookContext) {
+    world.commands().entity(context.entity).insert(UnInitializeWindow);
+}
+
+pub(crate) fn on_replace_parent_window(mut world: DeferredWorld, context: HookContext) {
+    let Some(ParentWindow(parent)) = world.get::<ParentWindow>(context.entity) else {
+        return;
+    };
+    let parent = *parent;
+    if let Some(mut replaced) = world.get_resource_mut::<ReplacedParentWindows>() {
+        replaced.0.insert(context.entity, parent);
+    }
+}
+
+pub(crate) fn on_remove_parent_window(mut world: DeferredWorld, context: HookContext) {
+    if let Some(mut replaced) = world.get_resource_mut::<ReplacedParentWindows>() {
+        replaced.0.remove(&context.entity);
+    }
+}
+
+/// Validates [`ParentWindow`] each time it is inserted, including when it is replaced.
+///
+/// Whether the parent window exists is checked later by [`validate_parent_windows`],
+/// so that the parent window can be spawned after the child window, e.g. while a scene is loaded.
+pub(crate) fn on_insert_parent_window(mut world: DeferredWorld, context: HookContext) {
+    let window = context.entity;
+    if !world.contains_resource::<ChildWindowPluginAdded>() {
+        log_error!(log_level(&world), "{}", ChildWindowError::MissingPlugin { window });
+        return;
+    }
+    let Some(ParentWindow(parent)) = world.get::<ParentWindow>(window) else {
+        return;
+    };
+    let parent = *parent;
+    let previous = world
+        .get_resource_mut::<ReplacedParentWindows>()
+        .and_then(|mut replaced| replaced.0.remove(&window));
+    let e = if window == parent {
+        ChildWindowError::SelfParent { window }
+    } else if is_descendant(&world, parent, window) {
+        ChildWindowError::ParentCycle { window, parent }
+    } else {
+        if previous.is_some_and(|previous| previous != parent) {
+            // Removing it first detaches the child window from the previous parent window, and attaches it again from scratch.
+            world
+                .commands()
+                .entity(window)
+                .remove::<ParentWindow>()
+                .insert((ParentWindow(parent), UnInitializeWindow));
+        }
+        return;
+    };
+    log_error!(log_level(&world), "{e}");
+    world.send_event(e);
+    world
+        .commands()
+        .entity(window)
+        .remove::<(ParentWindow, UnInitializeWindow)>();
+}
+
+/// Returns true if `window` is a descendant of `ancestor`.
+fn is_descendant(world: &DeferredWorld, window: Entity, ancestor: Entity) -> bool {
+    let mut visited = HashSet::new();
+    let mut current = window;
+    while let Some(ParentWindow(parent)) = world.get::<ParentWindow>(current) {
+        if *parent == ancestor {
+            return true;
+        }
+        if !visited.insert(current) {
+            return false;
+        }
+        current = *parent;
+    }
+    false
+}
+
+/// Validates the [`ParentWindow`]s inserted or changed since the last frame.
+///
+/// A parent window that does not exist or has no [`Window`] is only rejected here,
+/// because it may be spawned after the child window within the same frame.
+/// Child windows can be nested to any depth, but a [`ParentWindow`] that loops back to the child window itself is rejected.
+/// The loops made by inserting [`ParentWindow`] are already rejected by [`on_insert_parent_window`], so this catches the ones made by mutating it.
+fn validate_parent_windows(
+    mut commands: Commands,
+    mut ew: EventWriter<ChildWindowError>,
+    changed: Query<(Entity, &ParentWindow), Changed<ParentWindow>>,
+    parents: Query<&ParentWindow>,
+    windows: Query<Has<Window>>,
+    settings: Res<ChildWindowSettings>,
+) {
+    let mut rejected = Vec::new();
+    for (entity, ParentWindow(parent)) in changed.iter() {
+        let parent = *parent;
+        let e = match windows.get(parent) {
+            Err(_) => ChildWindowError::ParentNotFound { window: entity, parent },
+            Ok(false) => ChildWindowError::ParentNotWindow { window: entity, parent },
+            Ok(true) if is_ancestor_of(entity, parent, &parents, &rejected) => ChildWindowError::ParentCycle { window: entity, parent },
+            Ok(true) => continue,
+        };
+        log_error!(settings.log_level, "{e}");
+        ew.write(e);
+        commands
+            .entity(entity)
+            .remove::<(ParentWindow, UnInitializeWindow)>();
+        rejected.push(entity);
+    }
+}
+
+/// Returns true if `ancestor` is `window` itself or one of its ancestors.
+fn is_ancestor_of(
+    ancestor: Entity,
+    window: Entity,
+    parents: &Query<&ParentWindow>,
+    rejected: &[Entity],
+) -> bool {
+    let mut visited = HashSet::new();
+    let mut current = window;
+    loop {
+        if current == ancestor {
+            return true;
+        }
+        if rejected.contains(&current) || !visited.insert(current) {
+            return false;
+        }
+        let Ok(ParentWindow(parent)) = parents.get(current) else {
+            return false;
+        };
+        current = *parent;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::{mock_app, MockBackend};
+    use bevy::prelude::Events;
+    use std::sync::Arc;
+
+    #[test]
+    fn parent_window_can_be_spawned_later_in_the_same_frame() {
+        let mut app = mock_app(Arc::new(MockBackend::default()));
+        let parent = app.world_mut().spawn_empty().id();
+        let child = app.world_mut().spawn((Window::default(), ParentWindow(parent))).id();
+        app.world_mut().entity_mut(parent).insert(Window::default());
+        app.update();
+        assert!(app.world().entity(child).contains::<ParentWindow>());
+        assert!(app.world().resource::<Events<ChildWindowError>>().is_empty());
+    }
+
+    #[test]
+    fn parent_without_window_is_rejected() {
+        let mut app = mock_app(Arc::new(MockBackend::default()));
+        let parent = app.world_mut().spawn_empty().id();
+        let child = app.world_mut().spawn((Window::default(), ParentWindow(parent))).id();
+        app.update();
+        assert!(!app.world().entity(child).contains::<ParentWindow>());
+        let errors = app.world().resource::<Events<ChildWindowError>>();
+        assert_eq!(
+            errors.iter_current_update_events().copied().collect::<Vec<_>>(),
+            vec![ChildWindowError::ParentNotWindow { window: child, parent }],
+        );
+    }
+
+    #[test]
+    fn replacing_parent_window_attaches_again() {
+        let backend = Arc::new(MockBackend::default());
+        let mut app = mock_app(backend.clone());
+        let parent1 = app.world_mut().spawn(Window::default()).id();
+        let parent2 = app.world_mut().spawn(Window::default()).id();
+        let child = app.world_mut().spawn((Window::default(), ParentWindow(parent1))).id();
+        app.update();
+        assert!(!app.world().entity(child).contains::<UnInitializeWindow>());
+
+        app.world_mut().entity_mut(child).insert(ParentWindow(parent1));
+        app.world_mut().flush();
+        assert!(!app.world().entity(child).contains::<UnInitializeWindow>());
+
+        app.world_mut().entity_mut(child).insert(ParentWindow(parent2));
+        app.world_mut().flush();
+        assert!(app.world().entity(child).contains::<UnInitializeWindow>());
+        app.update();
+        assert!(!app.world().entity(child).contains::<UnInitializeWindow>());
+        assert_eq!(app.world().get::<ParentWindow>(child).map(|p| p.0), Some(parent2));
+        assert!(app.world().resource::<ReplacedParentWindows>().0.is_empty());
+    }
+}
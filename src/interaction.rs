@@ -0,0 +1,168 @@
+use crate::backend::{ActiveBackend, SharedState};
+use crate::ChildWindowSystems;
+use bevy::app::{App, Plugin, PreUpdate};
+use bevy::math::Rect;
+use bevy::prelude::{Entity, Event, EventWriter, IntoScheduleConfigs, Query, Res};
+use bevy::window::Window;
+
+pub(crate) struct ChildWindowInteractionPlugin;
+
+impl Plugin for ChildWindowInteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<ChildWindowDragStarted>()
+            .add_event::<ChildWindowDragged>()
+            .add_event::<ChildWindowDragEnded>()
+            .add_event::<ChildWindowResizeStarted>()
+            .add_event::<ChildWindowResized>()
+            .add_event::<ChildWindowResizeEnded>()
+            .add_systems(PreUpdate, write_interaction_events.in_set(ChildWindowSystems::Interaction));
+    }
+}
+
+/// Fired when the user starts moving the child window.
+///
+/// All rects of the drag and resize events are the client area of the child window, excluding its title bar and borders,
+/// in logical pixels relative to the top-left corner of the parent window's client area.
+#[derive(Event, Debug, Copy, Clone, PartialEq)]
+pub struct ChildWindowDragStarted {
+    /// The entity of the child window.
+    pub window: Entity,
+    /// The rect of the child window when the move started.
+    pub rect: Rect,
+}
+
+/// Fired each time the child window is moved by the user.
+#[derive(Event, Debug, Copy, Clone, PartialEq)]
+pub struct ChildWindowDragged {
+    /// The entity of the child window.
+    pub window: Entity,
+    /// The current rect of the child window.
+    pub rect: Rect,
+}
+
+/// Fired when the user stops moving the child window.
+#[derive(Event, Debug, Copy, Clone, PartialEq)]
+pub struct ChildWindowDragEnded {
+    /// The entity of the child window.
+    pub window: Entity,
+    /// The rect of the child window when the move ended.
+    pub rect: Rect,
+}
+
+/// Fired when the user starts resizing the child window.
+#[derive(Event, Debug, Copy, Clone, PartialEq)]
+pub struct ChildWindowResizeStarted {
+    /// The entity of the child window.
+    pub window: Entity,
+    /// The rect of the child window when the resize started.
+    pub rect: Rect,
+}
+
+/// Fired each time the child window is resized by the user.
+#[derive(Event, Debug, Copy, Clone, PartialEq)]
+pub struct ChildWindowResized {
+    /// The entity of the child window.
+    pub window: Entity,
+    /// The current rect of the child window.
+    pub rect: Rect,
+}
+
+/// Fired when the user stops resizing the child window.
+#[derive(Event, Debug, Copy, Clone, PartialEq)]
+pub struct ChildWindowResizeEnded {
+    /// The entity of the child window.
+    pub window: Entity,
+    /// The rect of the child window when the resize ended.
+    pub rect: Rect,
+}
+
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum InteractionKind {
+    DragStarted,
+    Dragged,
+    DragEnded,
+    ResizeStarted,
+    Resized,
+    ResizeEnded,
+}
+
+/// The drag or resize reported by the native event handlers.
+pub(crate) struct Interaction {
+    window: Entity,
+    kind: InteractionKind,
+    rect: Rect,
+    physical: bool,
+}
+
+impl SharedState {
+    /// Queues the interaction with `rect` in logical pixels.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub(crate) fn push_interaction(&self, window: Entity, kind: InteractionKind, rect: Rect) {
+        self.interactions.lock().unwrap().push(Interaction {
+            window,
+            kind,
+            rect,
+            physical: false,
+        });
+    }
+
+    /// Queues the interaction with `rect` in physical pixels.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub(crate) fn push_interaction_physical(&self, window: Entity, kind: InteractionKind, rect: Rect) {
+        self.interactions.lock().unwrap().push(Interaction {
+            window,
+            kind,
+            rect,
+            physical: true,
+        });
+    }
+}
+
+fn write_interaction_events(
+    (mut drag_started, mut dragged, mut drag_ended): (
+        EventWriter<ChildWindowDragStarted>,
+        EventWriter<ChildWindowDragged>,
+        EventWriter<ChildWindowDragEnded>,
+    ),
+    (mut resize_started, mut resized, mut resize_ended): (
+        EventWriter<ChildWindowResizeStarted>,
+        EventWriter<ChildWindowResized>,
+        EventWriter<ChildWindowResizeEnded>,
+    ),
+    windows: Query<&Window>,
+    backend: Res<ActiveBackend>,
+) {
+    let interactions = std::mem::take(&mut *backend.shared.interactions.lock().unwrap());
+    for Interaction { window, kind, rect, physical } in interactions {
+        let rect = if physical {
+            let Ok(w) = windows.get(window) else {
+                continue;
+            };
+            Rect::from_corners(rect.min / w.scale_factor(), rect.max / w.scale_factor())
+        } else {
+            rect
+        };
+        match kind {
+            InteractionKind::DragStarted => {
+                drag_started.write(ChildWindowDragStarted { window, rect });
+            }
+            InteractionKind::Dragged => {
+                dragged.write(ChildWindowDragged { window, rect });
+            }
+            InteractionKind::DragEnded => {
+                drag_ended.write(ChildWindowDragEnded { window, rect });
+            }
+            InteractionKind::ResizeStarted => {
+                resize_started.write(ChildWindowResizeStarted { window, rect });
+            }
+            InteractionKind::Resized => {
+                resized.write(ChildWindowResized { window, rect });
+            }
+            InteractionKind::ResizeEnded => {
+                resize_ended.write(ChildWindowResizeEnded { window, rect });
+            }
+        }
+    }
+}
@@ -22,16 +22,85 @@
 #![allow(clippy::type_complexity)]
 
 mod platform_impl;
-use bevy::app::{App, Plugin};
-use bevy::ecs::component::HookContext;
-use bevy::ecs::world::DeferredWorld;
-use bevy::prelude::{Component, Entity, Reflect, ReflectComponent, ReflectDefault, ReflectDeserialize, ReflectSerialize};
+mod backend;
+mod close;
+mod commands;
+mod decorations;
+mod diagnostics;
+mod dock;
+mod drag;
+mod error;
+mod focus;
+#[cfg(feature = "bevy_ui")]
+mod follow_ui;
+#[cfg(feature = "bevy_render")]
+mod follow_world;
+mod hierarchy;
+mod interaction;
+mod lock;
+mod minimize;
+mod modal;
+mod popup;
+mod resize;
+mod schedule;
+mod settings;
+mod tabs;
+mod tear_off;
+mod util;
+
+pub use backend::ChildWindowBackend;
+pub use close::{DefaultParentClosePolicy, ParentClosePolicy};
+pub use commands::{ChildWindowCommands, ChildWindowEntityCommands};
+pub use diagnostics::ChildWindowNativeResources;
+pub use dock::{ChildWindowDockPreview, ChildWindowDockTree, DockAxis, DockDropZone, DockNode, DockSide, DockTarget};
+pub use drag::{ChildWindowDragRegions, DragStartHit, StartChildWindowDrag};
+pub use error::ChildWindowError;
+pub use focus::{ChildWindowFocusChanged, ChildWindowFocusCycling, FocusChildWindow, FocusedChildWindow};
+#[cfg(feature = "bevy_ui")]
+pub use follow_ui::FollowUiNode;
+#[cfg(feature = "bevy_render")]
+pub use follow_world::{FollowWorldEntity, OffScreenPolicy};
+pub use interaction::{ChildWindowDragEnded, ChildWindowDragStarted, ChildWindowDragged, ChildWindowResizeEnded, ChildWindowResizeStarted, ChildWindowResized};
+pub use lock::ChildWindowLock;
+pub use minimize::{ChildWindowMinimized, MinimizedChildWindow, MinimizedChildWindows};
+pub use modal::{ChildWindowModal, ChildWindowModalBlocked, ModalBlockedWindows};
+pub use popup::{ChildWindowPopup, PopupPlacement};
+pub use resize::{ChildWindowResizeBorder, ResizeEdges};
+pub use schedule::ChildWindowSystems;
+pub use settings::{AttachRetryPolicy, ChildWindowLogLevel, ChildWindowSettings, ChildWindowSettingsOverride, Containment, TitleBarHeight};
+pub use tabs::{ActiveTab, ChildWindowTabGroup, TabOrder};
+pub use tear_off::{ChildWindowReembedded, ChildWindowTearOff, ChildWindowTornOff};
+use bevy::app::{App, Plugin, PostUpdate, PreUpdate, Update};
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::{Component, Entity, IntoScheduleConfigs, Reflect, ReflectComponent, ReflectDefault, ReflectDeserialize, ReflectSerialize};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[allow(missing_docs)]
 pub mod prelude {
     pub use crate::ChildWindowPlugin;
+    pub use crate::ChildWindowBackend;
     pub use crate::ParentWindow;
+    pub use crate::{DefaultParentClosePolicy, ParentClosePolicy};
+    pub use crate::{ChildWindowCommands, ChildWindowEntityCommands};
+    pub use crate::ChildWindowError;
+    pub use crate::{ChildWindowDockPreview, ChildWindowDockTree, DockAxis, DockDropZone, DockNode, DockSide, DockTarget};
+    pub use crate::{ChildWindowDragRegions, StartChildWindowDrag};
+    pub use crate::{ChildWindowFocusChanged, ChildWindowFocusCycling, FocusChildWindow, FocusedChildWindow};
+    pub use crate::{ChildWindowDragEnded, ChildWindowDragStarted, ChildWindowDragged, ChildWindowResizeEnded, ChildWindowResizeStarted, ChildWindowResized};
+    #[cfg(feature = "bevy_ui")]
+    pub use crate::FollowUiNode;
+    #[cfg(feature = "bevy_render")]
+    pub use crate::{FollowWorldEntity, OffScreenPolicy};
+    pub use crate::ChildWindowLock;
+    pub use crate::{ChildWindowMinimized, MinimizedChildWindows};
+    pub use crate::{ChildWindowModal, ChildWindowModalBlocked, ModalBlockedWindows};
+    pub use crate::{ChildWindowPopup, PopupPlacement};
+    pub use crate::{ChildWindowResizeBorder, ResizeEdges};
+    pub use crate::ChildWindowSystems;
+    pub use crate::{AttachRetryPolicy, ChildWindowLogLevel, ChildWindowSettings, ChildWindowSettingsOverride, Containment, TitleBarHeight};
+    pub use crate::{ActiveTab, ChildWindowTabGroup, TabOrder};
+    pub use crate::{ChildWindowReembedded, ChildWindowTearOff, ChildWindowTornOff};
 }
 
 /// Provides the feature to create a child window
@@ -42,33 +111,110 @@ pub mod prelude {
 /// # Example
 /// ```no_run
 /// use bevy::prelude::*;
-/// use bevy_child_window::ParentWindow;
+/// use bevy_child_window::prelude::*;
 ///
-/// fn spawn_child_window(
-///     mut commands: Commands,
-///    parent_window: Query<Entity, With<ParentWindow>>,
-/// ){
-///     commands.spawn((
-///         ParentWindow(parent_window.single().expect("Parent not found")),
-///         Window::default(),
-///    ));
+/// fn main() {
+///     App::new()
+///         .add_plugins((
+///             DefaultPlugins,
+///             // Attach the child windows right before they are synced and laid out in `PostUpdate`.
+///             ChildWindowPlugin::default().with_attach_schedule(PostUpdate),
+///         ))
+///         .run();
 /// }
 /// ```
-pub struct ChildWindowPlugin;
+pub struct ChildWindowPlugin {
+    /// The schedule where [`ChildWindowSystems::Attach`] runs.
+    ///
+    /// Defaults to `Update`.
+    ///
+    /// `bevy_winit` creates the native window after the frame in which [`Window`](bevy::window::Window) is spawned,
+    /// so a child window is attached in the next frame at the earliest, whichever schedule is chosen.
+    pub attach_schedule: InternedScheduleLabel,
+    /// The initial value of the [`ChildWindowSettings`] resource.
+    pub settings: ChildWindowSettings,
+    /// The backend that implements child windows natively.
+    ///
+    /// If `None`, the built-in backend of the target platform is used.
+    pub backend: Option<Arc<dyn ChildWindowBackend>>,
+}
+
+impl ChildWindowPlugin {
+    /// Sets [`ChildWindowPlugin::attach_schedule`].
+    pub fn with_attach_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.attach_schedule = schedule.intern();
+        self
+    }
+
+    /// Sets [`ChildWindowPlugin::backend`].
+    pub fn with_backend(mut self, backend: impl ChildWindowBackend) -> Self {
+        self.backend = Some(Arc::new(backend));
+        self
+    }
+}
+
+impl Default for ChildWindowPlugin {
+    fn default() -> Self {
+        Self {
+            attach_schedule: Update.intern(),
+            settings: ChildWindowSettings::default(),
+            backend: None,
+        }
+    }
+}
 
 impl Plugin for ChildWindowPlugin {
     fn build(&self, app: &mut App) {
         app
             .register_type::<ParentWindow>()
             .register_type::<UnInitializeWindow>()
-            .add_plugins(platform_impl::ChildWindowPlugin);
-
-        app
-            .world_mut()
-            .register_component_hooks::<ParentWindow>()
-            .on_add(|mut world: DeferredWorld, context: HookContext| {
-                world.commands().entity(context.entity).insert(UnInitializeWindow);
-            });
+            .configure_sets(PreUpdate, ChildWindowSystems::Interaction)
+            .configure_sets(self.attach_schedule, ChildWindowSystems::Attach)
+            .configure_sets(PostUpdate, (
+                ChildWindowSystems::Sync,
+                ChildWindowSystems::Layout,
+            )
+                .chain())
+            .add_plugins((
+                close::ChildWindowClosePlugin,
+                commands::ChildWindowCommandsPlugin,
+                decorations::ChildWindowDecorationsPlugin,
+                diagnostics::ChildWindowDiagnosticsPlugin,
+                dock::ChildWindowDockPlugin,
+                drag::ChildWindowDragPlugin,
+                focus::ChildWindowFocusPlugin,
+                hierarchy::ChildWindowHierarchyPlugin,
+                interaction::ChildWindowInteractionPlugin,
+                lock::ChildWindowLockPlugin,
+                minimize::ChildWindowMinimizePlugin,
+                modal::ChildWindowModalPlugin,
+            ))
+            .add_plugins((
+                popup::ChildWindowPopupPlugin,
+                resize::ChildWindowResizePlugin,
+                settings::ChildWindowSettingsPlugin {
+                    settings: self.settings,
+                    attach_schedule: self.attach_schedule,
+                },
+                tabs::ChildWindowTabsPlugin,
+                tear_off::ChildWindowTearOffPlugin,
+                backend::ChildWindowBackendPlugin {
+                    backend: self.backend.clone(),
+                    settings: self.settings,
+                    attach_schedule: self.attach_schedule,
+                },
+            ));
+        if self.attach_schedule == PostUpdate.intern() {
+            app.configure_sets(PostUpdate, ChildWindowSystems::Attach.before(ChildWindowSystems::Sync));
+        }
+        // The platform-specific systems only work together with the built-in backend.
+        if self.backend.is_none() {
+            app.add_plugins(platform_impl::ChildWindowPlugin);
+        }
+        #[cfg(feature = "bevy_ui")]
+        app.add_plugins(follow_ui::ChildWindowFollowUiPlugin);
+        #[cfg(feature = "bevy_render")]
+        app.add_plugins(follow_world::ChildWindowFollowWorldPlugin);
     }
 }
 
@@ -76,6 +222,13 @@ impl Plugin for ChildWindowPlugin {
 ///
 /// The window belonging to the same entity as this component will be displayed within the area of the parent window.
 ///
+/// The parent window may itself be a child window, in which case the child window is kept inside the client area of its immediate parent.
+/// It is validated when inserted, and if invalid, e.g. the target has no [`Window`](bevy::window::Window) or loops back to the child window,
+/// it is removed and reported with [`ChildWindowError`].
+/// Whether the target exists and has a [`Window`](bevy::window::Window) is checked at the beginning of the next frame,
+/// so the parent window can be spawned after the child window, e.g. in a scene.
+/// Replacing it with another parent window reparents the child window the same as [`ChildWindowEntityCommands::reparent`].
+///
 /// # Example
 /// ```no_run
 /// use bevy::prelude::*;
@@ -93,10 +246,14 @@ impl Plugin for ChildWindowPlugin {
 /// ```
 #[derive(Component, Reflect, Serialize, Deserialize)]
 #[reflect(Component, Serialize, Deserialize)]
+#[component(
+    on_add = hierarchy::on_add_parent_window,
+    on_insert = hierarchy::on_insert_parent_window,
+    on_replace = hierarchy::on_replace_parent_window,
+    on_remove = hierarchy::on_remove_parent_window,
+)]
 pub struct ParentWindow(pub Entity);
 
 #[derive(Component, Reflect, Serialize, Deserialize, Default)]
 #[reflect(Component, Serialize, Deserialize, Default)]
 struct UnInitializeWindow;
-
-
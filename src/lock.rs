@@ -0,0 +1,86 @@
+use crate::backend::{ActiveBackend, SharedState};
+use crate::ChildWindowSystems;
+use bevy::app::{App, Plugin, PostUpdate};
+use bevy::prelude::{Changed, Component, Entity, IntoScheduleConfigs, Query, Reflect, ReflectComponent, ReflectDefault, ReflectDeserialize, ReflectSerialize, RemovedComponents, Res};
+use serde::{Deserialize, Serialize};
+
+pub(crate) struct ChildWindowLockPlugin;
+
+impl Plugin for ChildWindowLockPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<ChildWindowLock>()
+            .add_systems(PostUpdate, sync_locks.in_set(ChildWindowSystems::Sync));
+    }
+}
+
+/// Pins the position and/or the size of the child window.
+///
+/// While locked, the child window can be neither moved nor resized by the user,
+/// including [`StartChildWindowDrag`](crate::StartChildWindowDrag) and the native title bar and borders.
+/// The lock can be toggled at any time by modifying or removing this component.
+///
+/// The window can still be moved and resized from Bevy code through [`Window`](bevy::window::Window).
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_child_window::prelude::*;
+///
+/// fn toggle_lock(
+///     mut locks: Query<&mut ChildWindowLock>,
+/// ){
+///     for mut lock in locks.iter_mut() {
+///         lock.position = !lock.position;
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub struct ChildWindowLock {
+    /// If true, the child window cannot be moved.
+    pub position: bool,
+    /// If true, the child window cannot be resized.
+    pub size: bool,
+}
+
+impl ChildWindowLock {
+    /// Locks both the position and the size.
+    pub const ALL: Self = Self {
+        position: true,
+        size: true,
+    };
+
+    /// Locks only the position.
+    pub const POSITION: Self = Self {
+        position: true,
+        size: false,
+    };
+
+    /// Locks only the size.
+    pub const SIZE: Self = Self {
+        position: false,
+        size: true,
+    };
+}
+
+impl SharedState {
+    /// Returns the lock of the child window, or the unlocked state if it has no [`ChildWindowLock`].
+    pub(crate) fn window_lock(&self, entity: Entity) -> ChildWindowLock {
+        self.locks.lock().unwrap().get(&entity).copied().unwrap_or_default()
+    }
+}
+
+fn sync_locks(
+    mut removed: RemovedComponents<ChildWindowLock>,
+    locks: Query<(Entity, &ChildWindowLock), Changed<ChildWindowLock>>,
+    backend: Res<ActiveBackend>,
+) {
+    let mut shared = backend.shared.locks.lock().unwrap();
+    for entity in removed.read() {
+        shared.remove(&entity);
+    }
+    for (entity, lock) in locks.iter() {
+        shared.insert(entity, *lock);
+    }
+}
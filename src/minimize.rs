@@ -0,0 +1,248 @@
+use crate::ParentWindow;
+use bevy::app::{App, Plugin, PostUpdate};
+use bevy::ecs::component::HookContext;
+use bevy::ecs::world::DeferredWorld;
+use bevy::math::Vec2;
+use bevy::prelude::{Changed, Commands, Component, DetectChangesMut, Entity, Query, Reflect, ReflectComponent, ReflectDefault, ReflectDeserialize, ReflectSerialize, Resource, With};
+use bevy::window::{Window, WindowPosition};
+use serde::{Deserialize, Serialize};
+
+pub(crate) struct ChildWindowMinimizePlugin;
+
+impl Plugin for ChildWindowMinimizePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<ChildWindowMinimized>()
+            .init_resource::<MinimizedChildWindows>()
+            .add_systems(PostUpdate, take_minimize_requests);
+    }
+}
+
+/// Minimizes the child window into the parent window instead of the OS dock or taskbar.
+///
+/// While this component is inserted, the native child window is hidden and listed in [`MinimizedChildWindows`],
+/// so apps can render a shelf or icon bar for it.
+/// Removing this component restores the child window to the rect and visibility it had before being minimized.
+///
+/// Calling [`Window::set_minimized`] on a child window inserts or removes this component instead of minimizing the native window.
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_child_window::prelude::*;
+///
+/// fn minimize(
+///     mut commands: Commands,
+///     child_windows: Query<Entity, With<ParentWindow>>,
+/// ){
+///     for entity in child_windows.iter() {
+///         commands.entity(entity).insert(ChildWindowMinimized);
+///     }
+/// }
+///
+/// fn restore(
+///     mut commands: Commands,
+///     minimized: Res<MinimizedChildWindows>,
+/// ){
+///     for window in minimized.iter() {
+///         commands.entity(window.entity).remove::<ChildWindowMinimized>();
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Serialize, Deserialize, Default, Debug, Copy, Clone, Eq, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+#[component(on_add = on_minimize, on_remove = on_restore)]
+pub struct ChildWindowMinimized;
+
+/// The list of the minimized child windows, in the order they were minimized.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct MinimizedChildWindows(Vec<MinimizedChildWindow>);
+
+impl MinimizedChildWindows {
+    /// Returns an iterator over all minimized child windows.
+    pub fn iter(&self) -> impl Iterator<Item=&MinimizedChildWindow> {
+        self.0.iter()
+    }
+
+    /// Returns an iterator over the minimized child windows belonging to `parent`.
+    pub fn iter_by_parent(&self, parent: Entity) -> impl Iterator<Item=&MinimizedChildWindow> {
+        self.0.iter().filter(move |window| window.parent == parent)
+    }
+
+    /// Returns true if `entity` is minimized.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.0.iter().any(|window| window.entity == entity)
+    }
+
+    /// Returns true if no child window is minimized.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// The entry of [`MinimizedChildWindows`].
+#[derive(Debug, Clone)]
+pub struct MinimizedChildWindow {
+    /// The entity of the minimized child window.
+    pub entity: Entity,
+    /// The entity of the parent window.
+    pub parent: Entity,
+    /// The title of the child window at the time it was minimized.
+    pub title: String,
+    restore_position: WindowPosition,
+    restore_size: Vec2,
+    restore_visible: bool,
+}
+
+fn on_minimize(mut world: DeferredWorld, context: HookContext) {
+    let Some(ParentWindow(parent)) = world.get::<ParentWindow>(context.entity) else {
+        return;
+    };
+    let parent = *parent;
+    let Some(window) = world.get::<Window>(context.entity) else {
+        return;
+    };
+    let entry = MinimizedChildWindow {
+        entity: context.entity,
+        parent,
+        title: window.title.clone(),
+        restore_position: window.position,
+        restore_size: window.resolution.size(),
+        restore_visible: window.visible,
+    };
+    let Some(mut minimized) = world.get_resource_mut::<MinimizedChildWindows>() else {
+        return;
+    };
+    minimized.0.push(entry);
+    if let Some(mut window) = world.get_mut::<Window>(context.entity) {
+        window.visible = false;
+    }
+}
+
+fn on_restore(mut world: DeferredWorld, context: HookContext) {
+    let Some(mut minimized) = world.get_resource_mut::<MinimizedChildWindows>() else {
+        return;
+    };
+    let Some(index) = minimized.0.iter().position(|window| window.entity == context.entity) else {
+        return;
+    };
+    let entry = minimized.0.remove(index);
+    let Some(mut window) = world.get_mut::<Window>(context.entity) else {
+        return;
+    };
+    window.position = entry.restore_position;
+    window.resolution.set(entry.restore_size.x, entry.restore_size.y);
+    window.visible = entry.restore_visible;
+}
+
+fn take_minimize_requests(
+    mut commands: Commands,
+    mut windows: Query<(Entity, &mut Window), (With<ParentWindow>, Changed<Window>)>,
+) {
+    for (entity, mut window) in windows.iter_mut() {
+        match window.bypass_change_detection().internal.take_minimize_request() {
+            Some(true) => {
+                commands.entity(entity).insert(ChildWindowMinimized);
+            }
+            Some(false) => {
+                commands.entity(entity).remove::<ChildWindowMinimized>();
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::{mock_app, MockBackend};
+    use bevy::math::IVec2;
+    use std::sync::Arc;
+
+    fn minimized_entities(app: &App) -> Vec<Entity> {
+        app.world().resource::<MinimizedChildWindows>().iter().map(|window| window.entity).collect()
+    }
+
+    #[test]
+    fn restored_to_state_before_minimized() {
+        let mut app = mock_app(Arc::new(MockBackend::default()));
+        let parent = app.world_mut().spawn(Window::default()).id();
+        let child = app
+            .world_mut()
+            .spawn((
+                Window {
+                    position: WindowPosition::At(IVec2::new(10, 20)),
+                    resolution: (400., 300.).into(),
+                    ..Default::default()
+                },
+                ParentWindow(parent),
+            ))
+            .id();
+        let hidden = app
+            .world_mut()
+            .spawn((
+                Window {
+                    visible: false,
+                    ..Default::default()
+                },
+                ParentWindow(parent),
+            ))
+            .id();
+        app.update();
+
+        app.world_mut().entity_mut(child).insert(ChildWindowMinimized);
+        app.world_mut().entity_mut(hidden).insert(ChildWindowMinimized);
+        assert!(!app.world().get::<Window>(child).unwrap().visible);
+        {
+            let mut window = app.world_mut().get_mut::<Window>(child).unwrap();
+            window.position = WindowPosition::At(IVec2::ZERO);
+            window.resolution.set(100., 100.);
+        }
+
+        app.world_mut().entity_mut(child).remove::<ChildWindowMinimized>();
+        app.world_mut().entity_mut(hidden).remove::<ChildWindowMinimized>();
+        let window = app.world().get::<Window>(child).unwrap();
+        assert_eq!(window.position, WindowPosition::At(IVec2::new(10, 20)));
+        assert_eq!(window.resolution.size(), Vec2::new(400., 300.));
+        assert!(window.visible);
+        assert!(!app.world().get::<Window>(hidden).unwrap().visible);
+        assert!(app.world().resource::<MinimizedChildWindows>().is_empty());
+    }
+
+    #[test]
+    fn listed_in_order_of_minimizing() {
+        let mut app = mock_app(Arc::new(MockBackend::default()));
+        let parent = app.world_mut().spawn(Window::default()).id();
+        let other_parent = app.world_mut().spawn(Window::default()).id();
+        let children = [parent, other_parent, parent].map(|parent| app.world_mut().spawn((Window::default(), ParentWindow(parent))).id());
+        app.update();
+
+        for child in [children[2], children[0], children[1]] {
+            app.world_mut().entity_mut(child).insert(ChildWindowMinimized);
+        }
+        assert_eq!(minimized_entities(&app), [children[2], children[0], children[1]]);
+        let minimized = app.world().resource::<MinimizedChildWindows>();
+        assert_eq!(minimized.iter_by_parent(parent).map(|window| window.entity).collect::<Vec<_>>(), [children[2], children[0]]);
+
+        app.world_mut().entity_mut(children[0]).remove::<ChildWindowMinimized>();
+        assert_eq!(minimized_entities(&app), [children[2], children[1]]);
+    }
+
+    #[test]
+    fn set_minimized_inserts_and_removes_component() {
+        let mut app = mock_app(Arc::new(MockBackend::default()));
+        let parent = app.world_mut().spawn(Window::default()).id();
+        let child = app.world_mut().spawn((Window::default(), ParentWindow(parent))).id();
+        app.update();
+
+        app.world_mut().get_mut::<Window>(child).unwrap().set_minimized(true);
+        app.update();
+        assert!(app.world().entity(child).contains::<ChildWindowMinimized>());
+        assert_eq!(minimized_entities(&app), [child]);
+
+        app.world_mut().get_mut::<Window>(child).unwrap().set_minimized(false);
+        app.update();
+        assert!(!app.world().entity(child).contains::<ChildWindowMinimized>());
+        assert!(app.world().get::<Window>(child).unwrap().visible);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::backend::ActiveBackend;
+use crate::util::retain_events;
+use crate::{ChildWindowMinimized, ParentWindow};
+use bevy::app::{App, First, Plugin};
+use bevy::ecs::event::{EventCursor, EventUpdates};
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::mouse::{MouseButtonInput, MouseWheel};
+use bevy::input::ButtonState;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Component, DetectChangesMut, Entity, Event, EventReader, EventWriter, Events, IntoScheduleConfigs, Local, NonSend, Query, Reflect, ReflectComponent, ReflectDefault, ReflectDeserialize, ReflectSerialize, Res, ResMut, Resource, With, Without};
+use bevy::window::{CursorMoved, Window};
+use bevy::winit::WinitWindows;
+use serde::{Deserialize, Serialize};
+
+pub(crate) struct ChildWindowModalPlugin;
+
+impl Plugin for ChildWindowModalPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<ChildWindowModal>()
+            .init_resource::<ModalBlockedWindows>()
+            .add_event::<ChildWindowModalBlocked>()
+            .add_systems(First, (
+                update_modal_blocked_windows,
+                filter_blocked_input_events,
+                raise_blocking_modal,
+            )
+                .chain()
+                .after(EventUpdates));
+    }
+}
+
+/// Makes the child window modal relative to its parent.
+///
+/// While a visible modal child window exists, the parent window and the other child windows of the same parent
+/// don't receive mouse and keyboard input.
+/// [`CursorMoved`], [`MouseButtonInput`], [`MouseWheel`] and [`KeyboardInput`] targeting them are removed
+/// before any system reads them, and clicking on them raises the modal window instead.
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::window::PrimaryWindow;
+/// use bevy_child_window::prelude::*;
+///
+/// fn spawn_confirmation_dialog(
+///     mut commands: Commands,
+///     parent: Query<Entity, With<PrimaryWindow>>,
+/// ){
+///     commands.spawn((
+///         ChildWindowModal,
+///         ParentWindow(parent.single().expect("Parent not found")),
+///         Window {
+///             title: "Confirm".to_string(),
+///             ..Default::default()
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Serialize, Deserialize, Default, Debug, Copy, Clone, Eq, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub struct ChildWindowModal;
+
+/// The windows that currently don't receive input because of a [`ChildWindowModal`].
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct ModalBlockedWindows(HashMap<Entity, Entity>);
+
+impl ModalBlockedWindows {
+    /// Returns true if the input to `window` is blocked.
+    pub fn is_blocked(&self, window: Entity) -> bool {
+        self.0.contains_key(&window)
+    }
+
+    /// Returns the modal child window blocking the input to `window`.
+    pub fn modal_of(&self, window: Entity) -> Option<Entity> {
+        self.0.get(&window).copied()
+    }
+
+    /// Returns an iterator over the blocked windows.
+    pub fn iter(&self) -> impl Iterator<Item=Entity> + '_ {
+        self.0.keys().copied()
+    }
+}
+
+/// Fired when a blocked window is clicked while a modal child window is shown.
+///
+/// The modal window is raised and focused automatically; this event can be used to flash it.
+#[derive(Event, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ChildWindowModalBlocked {
+    /// The entity of the modal child window.
+    pub modal: Entity,
+    /// The entity of the clicked window.
+    pub blocked: Entity,
+}
+
+fn update_modal_blocked_windows(
+    mut blocked_windows: ResMut<ModalBlockedWindows>,
+    modals: Query<(Entity, &Window, &ParentWindow), (With<ChildWindowModal>, Without<ChildWindowMinimized>)>,
+    children: Query<(Entity, &ParentWindow), (With<Window>, Without<ChildWindowModal>)>,
+) {
+    let mut blocked = HashMap::default();
+    for (modal, window, ParentWindow(parent)) in modals.iter() {
+        if !window.visible {
+            continue;
+        }
+        blocked.insert(*parent, modal);
+        for (child, _) in children.iter().filter(|(_, p)| p.0 == *parent) {
+            blocked.insert(child, modal);
+        }
+    }
+    blocked_windows.set_if_neq(ModalBlockedWindows(blocked));
+}
+
+fn filter_blocked_input_events(
+    blocked_windows: Res<ModalBlockedWindows>,
+    mut ew: EventWriter<ChildWindowModalBlocked>,
+    mut cursors: Local<(
+        EventCursor<CursorMoved>,
+        EventCursor<MouseWheel>,
+        EventCursor<KeyboardInput>,
+        EventCursor<MouseButtonInput>,
+    )>,
+    mut cursor_moved: Option<ResMut<Events<CursorMoved>>>,
+    mut mouse_button: Option<ResMut<Events<MouseButtonInput>>>,
+    mut mouse_wheel: Option<ResMut<Events<MouseWheel>>>,
+    mut keyboard: Option<ResMut<Events<KeyboardInput>>>,
+) {
+    let (cursor_moved_cursor, mouse_wheel_cursor, keyboard_cursor, mouse_button_cursor) = &mut *cursors;
+    if let Some(events) = cursor_moved.as_deref_mut() {
+        retain_events(events, cursor_moved_cursor, |e| !blocked_windows.is_blocked(e.window));
+    }
+    if let Some(events) = mouse_wheel.as_deref_mut() {
+        retain_events(events, mouse_wheel_cursor, |e| !blocked_windows.is_blocked(e.window));
+    }
+    if let Some(events) = keyboard.as_deref_mut() {
+        retain_events(events, keyboard_cursor, |e| !blocked_windows.is_blocked(e.window));
+    }
+    if let Some(events) = mouse_button.as_deref_mut() {
+        for e in mouse_button_cursor.clone().read(events) {
+            if e.state != ButtonState::Pressed {
+                continue;
+            }
+            if let Some(modal) = blocked_windows.modal_of(e.window) {
+                ew.write(ChildWindowModalBlocked {
+                    modal,
+                    blocked: e.window,
+                });
+            }
+        }
+        retain_events(events, mouse_button_cursor, |e| !blocked_windows.is_blocked(e.window));
+    }
+}
+
+fn raise_blocking_modal(
+    mut er: EventReader<ChildWindowModalBlocked>,
+    backend: Res<ActiveBackend>,
+    winit_windows: NonSend<WinitWindows>,
+) {
+    for ChildWindowModalBlocked { modal, .. } in er.read() {
+        backend.focus(&winit_windows, *modal);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::{mock_app, MockBackend};
+    use bevy::app::Update;
+    use bevy::input::mouse::MouseButton;
+    use std::sync::Arc;
+
+    /// The windows of the [`MouseButtonInput`] read in [`Update`].
+    #[derive(Resource, Default)]
+    struct ReadInputs(Vec<Entity>);
+
+    fn read_inputs(mut er: EventReader<MouseButtonInput>, mut read: ResMut<ReadInputs>) {
+        read.0.extend(er.read().map(|e| e.window));
+    }
+
+    fn click(window: Entity) -> MouseButtonInput {
+        MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+            window,
+        }
+    }
+
+    #[test]
+    fn modal_blocks_parent_and_siblings() {
+        let backend = Arc::new(MockBackend::default());
+        let mut app = mock_app(backend.clone());
+        app
+            .init_resource::<ReadInputs>()
+            .add_systems(Update, read_inputs);
+        let parent = app.world_mut().spawn(Window::default()).id();
+        let other = app.world_mut().spawn(Window::default()).id();
+        let sibling = app.world_mut().spawn((Window::default(), ParentWindow(parent))).id();
+        let modal = app.world_mut().spawn((Window::default(), ParentWindow(parent), ChildWindowModal)).id();
+        app.update();
+
+        let blocked = app.world().resource::<ModalBlockedWindows>();
+        assert_eq!(blocked.modal_of(parent), Some(modal));
+        assert_eq!(blocked.modal_of(sibling), Some(modal));
+        assert!(!blocked.is_blocked(modal));
+        assert!(!blocked.is_blocked(other));
+
+        app.world_mut().send_event_batch([click(parent), click(sibling), click(modal), click(other)]);
+        app.update();
+        assert_eq!(app.world().resource::<ReadInputs>().0, [modal, other]);
+        assert_eq!(*backend.raised.lock().unwrap(), [modal, modal]);
+    }
+
+    #[test]
+    fn hidden_or_minimized_modal_does_not_block() {
+        let mut app = mock_app(Arc::new(MockBackend::default()));
+        let parent = app.world_mut().spawn(Window::default()).id();
+        app.world_mut().spawn((
+            Window {
+                visible: false,
+                ..Default::default()
+            },
+            ParentWindow(parent),
+            ChildWindowModal,
+        ));
+        let modal = app.world_mut().spawn((Window::default(), ParentWindow(parent), ChildWindowModal)).id();
+        app.update();
+        assert_eq!(app.world().resource::<ModalBlockedWindows>().modal_of(parent), Some(modal));
+
+        app.world_mut().entity_mut(modal).insert(ChildWindowMinimized);
+        app.update();
+        assert_eq!(app.world().resource::<ModalBlockedWindows>().iter().count(), 0);
+    }
+}
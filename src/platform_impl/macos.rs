@@ -1,21 +1,28 @@
 mod delegate;
 
+use crate::backend::{ActiveBackend, SharedState};
+use crate::interaction::InteractionKind;
 use crate::platform_impl::macos::delegate::ChildWindowDelegate;
-use crate::{ParentWindow, UnInitializeWindow};
-use bevy::app::{App, Plugin, Update};
-use bevy::platform::collections::HashSet;
-use bevy::prelude::{any_with_component, Commands, Entity, IntoScheduleConfigs, NonSend, Query, ResMut, Resource, With};
-use bevy::window::Window;
+use crate::resize::ResizeDirection;
+use crate::tear_off::distance_outside;
+use crate::util::log_debug;
+use crate::{ChildWindowBackend, ChildWindowNativeResources, ChildWindowSystems, Containment, ModalBlockedWindows, ParentWindow, UnInitializeWindow};
+use bevy::app::{App, First, Plugin, PostUpdate};
+use bevy::math::{Rect, Vec2};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::{resource_changed, Changed, Entity, EventReader, IntoScheduleConfigs, NonSend, Query, Res, Without};
+use bevy::window::{Window, WindowClosed};
 use bevy::winit::WinitWindows;
 use block2::RcBlock;
 use objc2::ffi::NSInteger;
 use objc2::rc::Retained;
-use objc2::runtime::ProtocolObject;
+use objc2::runtime::{AnyObject, ProtocolObject};
 use objc2::Message;
-use objc2_app_kit::{NSEvent, NSEventMask, NSEventType, NSView, NSWindow, NSWindowOrderingMode, NSWindowStyleMask, NSWindowTitleVisibility};
-use objc2_foundation::{MainThreadMarker, NSPoint, NSRect};
-use std::cell::Cell;
-use std::mem::forget;
+use objc2_app_kit::{NSApplication, NSEvent, NSEventMask, NSEventType, NSView, NSWindow, NSWindowOrderingMode, NSWindowStyleMask, NSWindowTitleVisibility};
+use objc2_foundation::{MainThreadMarker, NSPoint, NSRect, NSSize};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::Arc;
 use std::ptr::{null_mut, NonNull};
 #[allow(deprecated)]
 use winit::raw_window_handle::HasRawWindowHandle;
@@ -26,8 +33,13 @@ pub struct ChildWindowPlugin;
 impl Plugin for ChildWindowPlugin {
     fn build(&self, app: &mut App) {
         app
-            .init_resource::<AlreadyRegisteredWindows>()
-            .add_systems(Update, convert_to_child_window.run_if(any_with_component::<UnInitializeWindow>));
+            .add_systems(First, (
+                release_closed_windows,
+                reattach_shown_child_windows,
+            ))
+            .add_systems(PostUpdate, sync_modal_blocked_windows
+                .run_if(resource_changed::<ModalBlockedWindows>)
+                .in_set(ChildWindowSystems::Sync));
     }
 }
 
@@ -35,54 +47,304 @@ impl Plugin for ChildWindowPlugin {
 enum CurrentStatus {
     None,
     Moving(NSInteger),
+    Resizing(NSInteger, ResizeDirection),
 }
 
-#[derive(Resource, Default)]
-struct AlreadyRegisteredWindows(HashSet<Entity>);
+/// The native state of the child windows of one app.
+#[derive(Default)]
+struct NativeWindows {
+    entities: HashMap<NSInteger, Entity>,
+    modal_blocked: HashSet<NSInteger>,
+    drag_statuses: HashMap<Entity, Rc<Cell<CurrentStatus>>>,
+    delegates: HashMap<Entity, Retained<ChildWindowDelegate>>,
+    event_monitors: HashMap<Entity, Retained<AnyObject>>,
+}
+
+thread_local! {
+    /// Keyed by the address of the [`SharedState`] of each app, since entities of different apps may be equal.
+    static NATIVE_WINDOWS: RefCell<HashMap<usize, NativeWindows>> = RefCell::new(HashMap::new());
+}
 
-fn convert_to_child_window(
-    mut commands: Commands,
-    mut already_registered_windows: ResMut<AlreadyRegisteredWindows>,
+fn native_windows_key(shared: &SharedState) -> usize {
+    shared as *const SharedState as usize
+}
+
+/// Calls `f` with the native state of the app owning `shared`.
+///
+/// `f` must not call into AppKit, which may call back into the event monitors or delegates while the state is borrowed.
+fn with_native_windows<R>(shared: &SharedState, f: impl FnOnce(&mut NativeWindows) -> R) -> R {
+    NATIVE_WINDOWS.with_borrow_mut(|windows| f(windows.entry(native_windows_key(shared)).or_default()))
+}
+
+/// Ordering out a child window detaches it from its parent,
+/// so it needs to be added back once it becomes visible again (e.g. restored from [`crate::ChildWindowMinimized`]).
+fn reattach_shown_child_windows(
     winit_windows: NonSend<WinitWindows>,
-    windows: Query<(Entity, &Window, &ParentWindow), With<UnInitializeWindow>>,
+    windows: Query<(Entity, &Window, &ParentWindow), (Changed<Window>, Without<UnInitializeWindow>)>,
 ) {
     for (entity, window, ParentWindow(parent_entity)) in windows.iter() {
-        let Some(child) = winit_windows.get_window(entity) else {
+        if !window.visible {
+            continue;
+        }
+        let Some(child_window) = winit_windows.get_window(entity).and_then(|w| obtain_ns_window(w)) else {
             continue;
         };
-        let Some(parent) = winit_windows.get_window(*parent_entity) else {
+        if unsafe { child_window.parentWindow() }.is_some() {
+            continue;
+        }
+        let Some(parent_window) = winit_windows.get_window(*parent_entity).and_then(|w| obtain_ns_window(w)) else {
             continue;
         };
-        let Some(child_window) = obtain_ns_window(child) else {
+        unsafe {
+            parent_window.addChildWindow_ordered(&child_window, NSWindowOrderingMode::Above);
+        }
+    }
+}
+
+/// Releases the native resources of closed windows.
+///
+/// Closing a parent window also removes the event monitor registered for it.
+fn release_closed_windows(
+    mut closed: EventReader<WindowClosed>,
+    winit_windows: NonSend<WinitWindows>,
+    backend: Res<ActiveBackend>,
+) {
+    for WindowClosed { window: entity } in closed.read() {
+        backend.detach(&winit_windows, *entity);
+        let monitor = with_native_windows(&backend.shared, |windows| {
+            windows.drag_statuses.remove(entity)?;
+            windows.event_monitors.remove(entity)
+        });
+        if let Some(monitor) = monitor {
+            unsafe {
+                NSEvent::removeMonitor(&monitor);
+            }
+        }
+    }
+}
+
+fn detach_child_window(child_window: &NSWindow) {
+    unsafe {
+        if let Some(parent_window) = child_window.parentWindow() {
+            parent_window.removeChildWindow(child_window);
+        }
+    }
+    child_window.setMovable(true);
+}
+
+/// Resets the drag status of the monitor that is moving the child window, e.g. when it is torn off,
+/// so that the monitor no longer swallows the drag.
+///
+/// Returns true if the child window was being moved.
+unsafe fn end_moving(
+    shared: &SharedState,
+    child_window: &NSWindow,
+) -> bool {
+    let moving = CurrentStatus::Moving(child_window.windowNumber());
+    let Some(status) = with_native_windows(shared, |windows| windows.drag_statuses.values().find(|status| status.get() == moving).cloned()) else {
+        return false;
+    };
+    status.set(CurrentStatus::None);
+    if let Some(parent_window) = child_window.parentWindow() {
+        notify_interaction(shared, &parent_window, child_window, InteractionKind::DragEnded);
+    }
+    true
+}
+
+/// Continues the current mouse drag as the native move of the detached window.
+unsafe fn hand_over_drag(window: &NSWindow) {
+    let Some(mtm) = MainThreadMarker::new() else {
+        return;
+    };
+    let Some(event) = NSApplication::sharedApplication(mtm).currentEvent() else {
+        return;
+    };
+    if matches!(event.r#type(), NSEventType::LeftMouseDown | NSEventType::LeftMouseDragged) {
+        window.performWindowDragWithEvent(&event);
+    }
+}
+
+fn sync_modal_blocked_windows(
+    winit_windows: NonSend<WinitWindows>,
+    blocked_windows: Res<ModalBlockedWindows>,
+    backend: Res<ActiveBackend>,
+) {
+    let blocked = blocked_windows
+        .iter()
+        .filter_map(|entity| winit_windows.get_window(entity).and_then(|w| obtain_ns_window(w)))
+        .map(|window| unsafe { window.windowNumber() })
+        .collect();
+    with_native_windows(&backend.shared, |windows| windows.modal_blocked = blocked);
+}
+
+/// Attaches child windows with `addChildWindow` and moves them with a local event monitor registered for each parent window.
+pub(crate) struct NativeBackend {
+    shared: Arc<SharedState>,
+}
+
+impl NativeBackend {
+    pub(crate) fn new(shared: Arc<SharedState>) -> Self {
+        Self { shared }
+    }
+}
+
+impl ChildWindowBackend for NativeBackend {
+    fn attach(&self, winit_windows: &WinitWindows, entity: Entity, window: &Window, parent: Entity) -> bool {
+        let Some(child_window) = winit_windows.get_window(entity).and_then(|w| obtain_ns_window(w)) else {
+            return false;
+        };
+        let Some(parent_window) = winit_windows.get_window(parent).and_then(|w| obtain_ns_window(w)) else {
+            return false;
+        };
+        let window_num = unsafe { child_window.windowNumber() };
+        with_native_windows(&self.shared, |windows| windows.entities.insert(window_num, entity));
+        settings_windows(entity, window, &child_window, &parent_window, self.shared.clone());
+        if !with_native_windows(&self.shared, |windows| windows.drag_statuses.contains_key(&parent)) {
+            if let Some(monitor) = unsafe { register_ns_event(parent, parent_window, self.shared.clone()) } {
+                with_native_windows(&self.shared, |windows| windows.event_monitors.insert(parent, monitor));
+            }
+        }
+        true
+    }
+
+    fn detach(&self, winit_windows: &WinitWindows, entity: Entity) {
+        let delegate = with_native_windows(&self.shared, |windows| {
+            let delegate = windows.delegates.remove(&entity)?;
+            windows.entities.retain(|_, e| *e != entity);
+            Some(delegate)
+        });
+        let Some(delegate) = delegate else {
+            return;
+        };
+        if let Some(child_window) = winit_windows.get_window(entity).and_then(|w| obtain_ns_window(w)) {
+            let moving = unsafe { end_moving(&self.shared, &child_window) };
+            child_window.setDelegate(None);
+            detach_child_window(&child_window);
+            if moving {
+                unsafe {
+                    hand_over_drag(&child_window);
+                }
+            }
+            log_debug!(self.shared.log_level(), "Detached the child window {entity}");
+        }
+        drop(delegate);
+    }
+
+    /// Sets the frame of the child window so that its content rect becomes `rect`.
+    fn set_rect(&self, winit_windows: &WinitWindows, entity: Entity, _parent: Entity, rect: Rect) {
+        let Some(child_window) = winit_windows.get_window(entity).and_then(|w| obtain_ns_window(w)) else {
+            return;
+        };
+        let Some(parent_window) = (unsafe { child_window.parentWindow() }) else {
+            return;
+        };
+        let p = parent_window.contentRectForFrameRect(parent_window.frame());
+        let content = NSRect::new(
+            NSPoint::new(p.origin.x + rect.min.x as f64, p.origin.y + p.size.height - rect.max.y as f64),
+            NSSize::new(rect.width() as f64, rect.height() as f64),
+        );
+        child_window.setFrame_display(child_window.frameRectForContentRect(content), true);
+    }
+
+    fn raise(&self, winit_windows: &WinitWindows, entity: Entity) {
+        let Some(child_window) = winit_windows.get_window(entity).and_then(|w| obtain_ns_window(w)) else {
+            return;
+        };
+        let Some(parent_window) = (unsafe { child_window.parentWindow() }) else {
+            return;
+        };
+        unsafe {
+            bring_to_front_child_window(&parent_window, &child_window);
+        }
+    }
+
+    fn focus(&self, winit_windows: &WinitWindows, entity: Entity) {
+        self.raise(winit_windows, entity);
+        if let Some(window) = winit_windows.get_window(entity) {
+            window.focus_window();
+        }
+    }
+
+    fn query_rect(&self, winit_windows: &WinitWindows, entity: Entity, _parent: Entity) -> Option<Rect> {
+        let child_window = winit_windows.get_window(entity).and_then(|w| obtain_ns_window(w))?;
+        unsafe {
+            let parent_window = child_window.parentWindow()?;
+            Some(parent_relative_rect(&parent_window, &child_window))
+        }
+    }
+
+    /// Hands the current mouse drag to the move logic of the monitor registered for the parent window.
+    fn start_drag(&self, winit_windows: &WinitWindows, entity: Entity, parent: Entity) {
+        let Some(child_window) = winit_windows.get_window(entity).and_then(|w| obtain_ns_window(w)) else {
             return;
         };
-        let Some(parent_window) = obtain_ns_window(parent) else {
+        let Some(status) = with_native_windows(&self.shared, |windows| windows.drag_statuses.get(&parent).cloned()) else {
             return;
         };
-        commands.entity(entity).remove::<UnInitializeWindow>();
-        settings_windows(window, &child_window, &parent_window);
-        if !already_registered_windows.0.contains(parent_entity) {
+        unsafe {
+            let Some(parent_window) = child_window.parentWindow() else {
+                return;
+            };
+            bring_to_front_child_window(&parent_window, &child_window);
+            status.set(CurrentStatus::Moving(child_window.windowNumber()));
+            notify_interaction(&self.shared, &parent_window, &child_window, InteractionKind::DragStarted);
+        }
+    }
+
+    /// Re-applies the child window rules after `winit` applied the changes of [`Window`].
+    fn apply_decorations(&self, winit_windows: &WinitWindows, entity: Entity, window: &Window) {
+        let Some(child_window) = winit_windows.get_window(entity).and_then(|w| obtain_ns_window(w)) else {
+            return;
+        };
+        apply_child_window_rules(window, &child_window);
+    }
+
+    fn native_resources(&self) -> ChildWindowNativeResources {
+        with_native_windows(&self.shared, |windows| ChildWindowNativeResources {
+            child_windows: windows.delegates.len(),
+            event_monitors: windows.event_monitors.len(),
+        })
+    }
+}
+
+impl Drop for NativeBackend {
+    /// Removes the native state of the app, so that a later app whose [`SharedState`] gets the same address doesn't inherit it.
+    fn drop(&mut self) {
+        let key = native_windows_key(&self.shared);
+        let Some(windows) = NATIVE_WINDOWS.try_with(|windows| windows.borrow_mut().remove(&key)).ok().flatten() else {
+            return;
+        };
+        for monitor in windows.event_monitors.values() {
             unsafe {
-                register_ns_event(window, parent_window);
+                NSEvent::removeMonitor(monitor);
             }
-            already_registered_windows.0.insert(*parent_entity);
         }
     }
 }
 
 fn settings_windows(
+    entity: Entity,
     window: &Window,
     child_window: &NSWindow,
     parent_window: &NSWindow,
+    shared: Arc<SharedState>,
 ) {
     unsafe {
         parent_window.addChildWindow_ordered(child_window, NSWindowOrderingMode::Above);
     }
 
-    let delegate = ChildWindowDelegate::new(MainThreadMarker::new().unwrap());
+    let delegate = ChildWindowDelegate::new(MainThreadMarker::new().unwrap(), shared.clone());
     child_window.setDelegate(Some(ProtocolObject::from_ref(&*delegate)));
-    forget(delegate);
+    // The window only holds a weak reference to its delegate.
+    with_native_windows(&shared, |windows| windows.delegates.insert(entity, delegate));
 
+    apply_child_window_rules(window, child_window);
+}
+
+fn apply_child_window_rules(
+    window: &Window,
+    child_window: &NSWindow,
+) {
     child_window.setMovable(false);
     child_window.setStyleMask(style_mask(window));
     child_window.setTitleVisibility(if window.titlebar_show_title {
@@ -107,44 +369,79 @@ fn style_mask(window: &Window) -> NSWindowStyleMask {
     mask
 }
 
+/// Returns the monitor, which must be passed to [`NSEvent::removeMonitor`] when the parent window is closed.
 unsafe fn register_ns_event(
-    window: &Window,
+    parent_entity: Entity,
     parent_window: Retained<NSWindow>,
-) {
-    let movable_by_window_background = window.movable_by_window_background;
-    let status = Cell::new(CurrentStatus::None);
+    shared: Arc<SharedState>,
+) -> Option<Retained<AnyObject>> {
+    let status = Rc::new(Cell::new(CurrentStatus::None));
+    with_native_windows(&shared, |windows| windows.drag_statuses.insert(parent_entity, status.clone()));
+    // AppKit copies the handler, so it is released by `removeMonitor`.
     NSEvent::addLocalMonitorForEventsMatchingMask_handler(
-        NSEventMask::LeftMouseDragged | NSEventMask::LeftMouseDown | NSEventMask::LeftMouseUp | NSEventMask::MouseMoved,
-        Box::leak(Box::new(RcBlock::new(move |event: NonNull<NSEvent>| {
+        NSEventMask::LeftMouseDragged | NSEventMask::LeftMouseDown | NSEventMask::LeftMouseUp,
+        &RcBlock::new(move |event: NonNull<NSEvent>| {
             let e = &*event.as_ptr();
             match (e.r#type(), status.get()) {
                 (NSEventType::LeftMouseDown, CurrentStatus::None) => {
-                    transition_to_move(movable_by_window_background, &parent_window, &status, e);
+                    let window_num = e.windowNumber();
+                    if with_native_windows(&shared, |windows| windows.modal_blocked.contains(&window_num)) {
+                        return event.as_ptr();
+                    }
+                    raise_clicked_child_window(&shared, &parent_window, e);
+                    if !transition_to_resize(&shared, &parent_window, &status, e) {
+                        transition_to_move(&shared, &parent_window, &status, e);
+                    }
                 }
-                (NSEventType::LeftMouseUp, _) => {
+                (NSEventType::LeftMouseUp, current) => {
                     status.set(CurrentStatus::None);
+                    let (target_num, kind) = match current {
+                        CurrentStatus::Moving(target_num) => (target_num, InteractionKind::DragEnded),
+                        CurrentStatus::Resizing(target_num, _) => (target_num, InteractionKind::ResizeEnded),
+                        CurrentStatus::None => return event.as_ptr(),
+                    };
+                    if let Some(child_window) = find_child_window(&parent_window, target_num) {
+                        notify_interaction(&shared, &parent_window, &child_window, kind);
+                    }
                 }
                 (NSEventType::LeftMouseDragged, CurrentStatus::Moving(target_num)) => {
                     let Some(child_window) = find_child_window(&parent_window, target_num) else {
                         return null_mut();
                     };
-                    move_child_window(&parent_window, &child_window, e.deltaX(), e.deltaY());
+                    move_child_window(&shared, &parent_window, &child_window, e.deltaX(), e.deltaY());
+                    notify_interaction(&shared, &parent_window, &child_window, InteractionKind::Dragged);
+                    notify_drag_overshoot(&shared, &parent_window, &child_window);
+                }
+                (NSEventType::LeftMouseDragged, CurrentStatus::Resizing(target_num, dir)) => {
+                    let Some(child_window) = find_child_window(&parent_window, target_num) else {
+                        return null_mut();
+                    };
+                    resize_child_window(&shared, &parent_window, &child_window, dir, e.deltaX(), e.deltaY());
+                    notify_interaction(&shared, &parent_window, &child_window, InteractionKind::Resized);
                 }
                 _ => {}
             }
             event.as_ptr()
-        }))),
-    );
+        }),
+    )
 }
 
 #[inline]
 unsafe fn move_child_window(
+    shared: &SharedState,
     parent_window: &NSWindow,
     child_window: &NSWindow,
     delta_x: f64,
     delta_y: f64,
 ) {
     let c = child_window.frame();
+    if !is_contained(shared, child_window) {
+        child_window.setFrame_display(NSRect::new(
+            NSPoint::new(c.origin.x + delta_x, c.origin.y - delta_y),
+            c.size,
+        ), false);
+        return;
+    }
     let p = parent_window.contentRectForFrameRect(parent_window.frame());
     let x = p.origin.x.max(c.origin.x + delta_x);
     let x = x.min(p.origin.x + p.size.width - c.size.width);
@@ -158,6 +455,96 @@ unsafe fn move_child_window(
     ), false);
 }
 
+#[inline]
+unsafe fn resize_child_window(
+    shared: &SharedState,
+    parent_window: &NSWindow,
+    child_window: &NSWindow,
+    dir: ResizeDirection,
+    delta_x: f64,
+    delta_y: f64,
+) {
+    let c = child_window.frame();
+    let p = if is_contained(shared, child_window) {
+        parent_window.contentRectForFrameRect(parent_window.frame())
+    } else {
+        // Large enough that the clamps below have no effect.
+        NSRect::new(NSPoint::new(f64::MIN / 2., f64::MIN / 2.), NSSize::new(f64::MAX, f64::MAX))
+    };
+    let min_size = window_entity(shared, child_window.windowNumber())
+        .map(|entity| shared.resize_min_size(entity))
+        .unwrap_or_default();
+    let (min_w, min_h) = (min_size.x as f64, min_size.y as f64);
+    let (mut min_x, mut max_x) = (c.origin.x, c.origin.x + c.size.width);
+    let (mut min_y, mut max_y) = (c.origin.y, c.origin.y + c.size.height);
+    if dir.left {
+        min_x = (min_x + delta_x).min(max_x - min_w).max(p.origin.x);
+    } else if dir.right {
+        max_x = (max_x + delta_x).max(min_x + min_w).min(p.origin.x + p.size.width);
+    }
+    if dir.bottom {
+        min_y = (min_y - delta_y).min(max_y - min_h).max(p.origin.y);
+    } else if dir.top {
+        max_y = (max_y - delta_y).max(min_y + min_h).min(p.origin.y + p.size.height);
+    }
+
+    child_window.setFrame_display(NSRect::new(
+        NSPoint::new(min_x, min_y),
+        NSSize::new(max_x - min_x, max_y - min_y),
+    ), true);
+}
+
+/// Returns the content rect of the child window relative to the top-left corner of the parent window's content rect.
+unsafe fn parent_relative_rect(
+    parent_window: &NSWindow,
+    child_window: &NSWindow,
+) -> Rect {
+    let p = parent_window.contentRectForFrameRect(parent_window.frame());
+    let c = child_window.contentRectForFrameRect(child_window.frame());
+    let x = c.origin.x - p.origin.x;
+    let y = (p.origin.y + p.size.height) - (c.origin.y + c.size.height);
+    Rect::new(x as f32, y as f32, (x + c.size.width) as f32, (y + c.size.height) as f32)
+}
+
+/// Reports how far the cursor is outside the parent window's content rect, so that the child window can be torn off.
+unsafe fn notify_drag_overshoot(
+    shared: &SharedState,
+    parent_window: &NSWindow,
+    child_window: &NSWindow,
+) {
+    let Some(entity) = window_entity(shared, child_window.windowNumber()) else {
+        return;
+    };
+    let p = parent_window.contentRectForFrameRect(parent_window.frame());
+    let area = Rect::new(p.origin.x as f32, p.origin.y as f32, (p.origin.x + p.size.width) as f32, (p.origin.y + p.size.height) as f32);
+    let cursor = NSEvent::mouseLocation();
+    let distance = distance_outside(area, Vec2::new(cursor.x as f32, cursor.y as f32));
+    if 0. < distance {
+        shared.push_drag_overshoot(entity, distance);
+    }
+}
+
+unsafe fn notify_interaction(
+    shared: &SharedState,
+    parent_window: &NSWindow,
+    child_window: &NSWindow,
+    kind: InteractionKind,
+) {
+    if let Some(entity) = window_entity(shared, child_window.windowNumber()) {
+        shared.push_interaction(entity, kind, parent_relative_rect(parent_window, child_window));
+    }
+}
+
+/// Returns true if the child window is kept inside the content rect of its parent by [`Containment`].
+fn is_contained(shared: &SharedState, child_window: &NSWindow) -> bool {
+    window_entity(shared, unsafe { child_window.windowNumber() })
+        .is_none_or(|entity| shared.window_settings(entity).containment == Containment::ClientArea)
+}
+
+fn window_entity(shared: &SharedState, window_num: NSInteger) -> Option<Entity> {
+    with_native_windows(shared, |windows| windows.entities.get(&window_num).copied())
+}
+
 unsafe fn find_child_window(window: &NSWindow, window_num: NSInteger) -> Option<Retained<NSWindow>> {
     for child in window.childWindows()?.iter() {
         if child.windowNumber() == window_num {
@@ -198,18 +585,66 @@ unsafe fn bring_to_front_child_window(
     child_window.becomeKeyWindow();
 }
 
+unsafe fn raise_clicked_child_window(
+    shared: &SharedState,
+    parent_window: &NSWindow,
+    e: &NSEvent,
+) {
+    let Some(child_window) = find_child_window(parent_window, e.windowNumber()) else {
+        return;
+    };
+    if window_entity(shared, e.windowNumber()).is_some_and(|entity| shared.window_settings(entity).raise_on_click) {
+        bring_to_front_child_window(parent_window, &child_window);
+    }
+}
+
+unsafe fn transition_to_resize(
+    shared: &SharedState,
+    parent_window: &NSWindow,
+    status: &Cell<CurrentStatus>,
+    e: &NSEvent,
+) -> bool {
+    let Some(child_window) = find_child_window(parent_window, e.windowNumber()) else {
+        return false;
+    };
+    let Some(entity) = window_entity(shared, e.windowNumber()) else {
+        return false;
+    };
+    if shared.window_lock(entity).size {
+        return false;
+    }
+    let content = child_window.contentRectForFrameRect(child_window.frame());
+    let location = e.locationInWindow();
+    let position = Vec2::new(location.x as f32, (content.size.height - location.y) as f32);
+    let size = Vec2::new(content.size.width as f32, content.size.height as f32);
+    let Some(dir) = shared.hit_test_resize_border(entity, position, size) else {
+        return false;
+    };
+    status.set(CurrentStatus::Resizing(e.windowNumber(), dir));
+    notify_interaction(shared, parent_window, &child_window, InteractionKind::ResizeStarted);
+    true
+}
+
 unsafe fn transition_to_move(
-    movable_by_window_background: bool,
+    shared: &SharedState,
     parent_window: &NSWindow,
     status: &Cell<CurrentStatus>,
     e: &NSEvent,
 ) {
-    if let Some(child_window) = find_child_window(parent_window, e.windowNumber()) {
-        if movable_by_window_background || child_window.contentRectForFrameRect(child_window.frame()).size.height <= e.locationInWindow().y {
-            bring_to_front_child_window(parent_window, &child_window);
-            status.set(CurrentStatus::Moving(e.windowNumber()));
-        }
+    let Some(child_window) = find_child_window(parent_window, e.windowNumber()) else {
+        return;
     };
+    let Some(entity) = window_entity(shared, e.windowNumber()) else {
+        return;
+    };
+    let content_height = child_window.contentRectForFrameRect(child_window.frame()).size.height;
+    let location = e.locationInWindow();
+    let in_title_bar = content_height <= location.y;
+    let position = Vec2::new(location.x as f32, (content_height - location.y) as f32);
+    if shared.starts_drag(entity, position, in_title_bar) {
+        status.set(CurrentStatus::Moving(e.windowNumber()));
+        notify_interaction(shared, parent_window, &child_window, InteractionKind::DragStarted);
+    }
 }
 
 
@@ -1,22 +1,21 @@
+use crate::backend::SharedState;
+use crate::interaction::InteractionKind;
+use crate::Containment;
+use crate::platform_impl::macos::{notify_interaction, window_entity};
+use crate::resize::ResizeDirection;
 use objc2::rc::Retained;
 use objc2::runtime::{NSObject, NSObjectProtocol};
 use objc2::{define_class, msg_send, DefinedClass, MainThreadOnly};
 use objc2_app_kit::{NSWindow, NSWindowDelegate};
 use objc2_foundation::{MainThreadMarker, NSNotification, NSPoint, NSRect, NSSize};
 use std::cell::Cell;
+use std::sync::Arc;
 
 
 pub struct ChildWindowIVars {
     window_origin: Cell<NSRect>,
     dir: Cell<Option<ResizeDirection>>,
-}
-
-#[derive(Copy, Clone)]
-pub struct ResizeDirection {
-    left: bool,
-    right: bool,
-    top: bool,
-    bottom: bool,
+    shared: Arc<SharedState>,
 }
 
 define_class! {
@@ -41,6 +40,12 @@ define_class! {
             on_did_resize(self.ivars(), notification);
         }
 
+        #[inline]
+        #[unsafe(method(windowDidEndLiveResize:))]
+        unsafe fn window_did_end_live_resize(&self, notification: &NSNotification){
+            on_resize_end(self.ivars(), notification);
+        }
+
         #[inline]
         #[unsafe(method(windowWillResize:toSize:))]
         unsafe fn window_will_resize(&self, window: &NSWindow, proposed_size: NSSize) -> NSSize {
@@ -50,11 +55,12 @@ define_class! {
 }
 
 impl ChildWindowDelegate {
-    pub fn new(mtm: MainThreadMarker) -> Retained<Self> {
+    pub fn new(mtm: MainThreadMarker, shared: Arc<SharedState>) -> Retained<Self> {
         let this = mtm.alloc();
         let this = this.set_ivars(ChildWindowIVars {
             window_origin: Cell::new(NSRect::new(NSPoint::new(0., 0.), NSSize::new(0., 0.))),
             dir: Cell::new(None),
+            shared,
         });
         unsafe { msg_send![super(this), init] }
     }
@@ -71,21 +77,48 @@ unsafe fn on_resize_start(
     let ns_window: Retained<NSWindow> = Retained::cast_unchecked(obj);
     i_vars.window_origin.set(ns_window.frame());
     i_vars.dir.set(None);
+    notify_live_resize(i_vars, &ns_window, InteractionKind::ResizeStarted);
 }
 
 #[inline]
-unsafe fn on_did_resize(
+unsafe fn on_resize_end(
     i_vars: &ChildWindowIVars,
     notification: &NSNotification,
 ) {
-    if i_vars.dir.get().is_some() {
+    let Some(obj) = notification.object() else {
         return;
+    };
+    let ns_window: Retained<NSWindow> = Retained::cast_unchecked(obj);
+    notify_live_resize(i_vars, &ns_window, InteractionKind::ResizeEnded);
+}
+
+unsafe fn notify_live_resize(
+    i_vars: &ChildWindowIVars,
+    ns_window: &NSWindow,
+    kind: InteractionKind,
+) {
+    if let Some(parent_window) = ns_window.parentWindow() {
+        notify_interaction(&i_vars.shared, &parent_window, ns_window, kind);
     }
+}
+
+#[inline]
+unsafe fn on_did_resize(
+    i_vars: &ChildWindowIVars,
+    notification: &NSNotification,
+) {
     let Some(obj) = notification.object() else {
         return;
     };
-
     let ns_window: Retained<NSWindow> = Retained::cast_unchecked(obj);
+    if !ns_window.inLiveResize() {
+        return;
+    }
+    notify_live_resize(i_vars, &ns_window, InteractionKind::Resized);
+    if i_vars.dir.get().is_some() {
+        return;
+    }
+
     let start = i_vars.window_origin.get();
     let current = ns_window.frame();
     i_vars.dir.set(Some(ResizeDirection {
@@ -131,6 +164,14 @@ fn on_will_resize(
     window: &NSWindow,
     mut size: NSSize,
 ) -> NSSize {
+    let window_num = unsafe { window.windowNumber() };
+    let entity = window_entity(&i_vars.shared, window_num);
+    if entity.is_some_and(|entity| i_vars.shared.window_lock(entity).size) {
+        return window.frame().size;
+    }
+    if entity.is_some_and(|entity| i_vars.shared.window_settings(entity).containment == Containment::Unconstrained) {
+        return size;
+    }
     let Some(dir) = i_vars.dir.get() else {
         return size;
     };
@@ -1,4 +1,13 @@
+use crate::backend::SharedState;
+use crate::util::log_warn;
+use crate::ChildWindowBackend;
 use bevy::app::Plugin;
+use bevy::math::{Rect, Vec2};
+use bevy::prelude::Entity;
+use bevy::window::Window;
+use bevy::winit::WinitWindows;
+use std::sync::Arc;
+use winit::dpi::{LogicalSize, PhysicalPosition};
 
 /// For unsupported platforms, this plugin is used.
 ///
@@ -7,4 +16,71 @@ pub struct ChildWindowPlugin;
 
 impl Plugin for ChildWindowPlugin {
     fn build(&self, _app: &mut bevy::app::App) {}
-}
\ No newline at end of file
+}
+
+/// Child windows can't be embedded on unsupported platforms, so they stay top-level windows
+/// that are positioned relative to the client area of their parent windows.
+pub(crate) struct NativeBackend {
+    shared: Arc<SharedState>,
+}
+
+impl NativeBackend {
+    pub(crate) fn new(shared: Arc<SharedState>) -> Self {
+        Self {
+            shared,
+        }
+    }
+}
+
+impl ChildWindowBackend for NativeBackend {
+    /// Only waits for the native windows to be created, so that the child window can be controlled as a top-level window.
+    fn attach(&self, winit_windows: &WinitWindows, entity: Entity, _window: &Window, parent: Entity) -> bool {
+        winit_windows.get_window(entity).is_some() && winit_windows.get_window(parent).is_some()
+    }
+
+    fn detach(&self, _winit_windows: &WinitWindows, _entity: Entity) {}
+
+    /// The child window is a top-level window on unsupported platforms,
+    /// so its outer position is offset by its decorations so that its client area is placed at `rect`.
+    fn set_rect(&self, winit_windows: &WinitWindows, entity: Entity, parent: Entity, rect: Rect) {
+        let (Some(window), Some(parent)) = (winit_windows.get_window(entity), winit_windows.get_window(parent)) else {
+            return;
+        };
+        let (Ok(origin), Ok(inner), Ok(outer)) = (parent.inner_position(), window.inner_position(), window.outer_position()) else {
+            return;
+        };
+        let scale_factor = window.scale_factor();
+        window.set_outer_position(PhysicalPosition::new(
+            origin.x + (rect.min.x as f64 * scale_factor).round() as i32 - (inner.x - outer.x),
+            origin.y + (rect.min.y as f64 * scale_factor).round() as i32 - (inner.y - outer.y),
+        ));
+        let _ = window.request_inner_size(LogicalSize::new(rect.width(), rect.height()));
+    }
+
+    fn raise(&self, _winit_windows: &WinitWindows, _entity: Entity) {}
+
+    fn focus(&self, winit_windows: &WinitWindows, entity: Entity) {
+        if let Some(window) = winit_windows.get_window(entity) {
+            window.focus_window();
+        }
+    }
+
+    fn query_rect(&self, winit_windows: &WinitWindows, entity: Entity, parent: Entity) -> Option<Rect> {
+        let (window, parent) = (winit_windows.get_window(entity)?, winit_windows.get_window(parent)?);
+        let position = window.inner_position().ok()?;
+        let origin = parent.inner_position().ok()?;
+        let scale_factor = window.scale_factor() as f32;
+        let min = Vec2::new((position.x - origin.x) as f32, (position.y - origin.y) as f32) / scale_factor;
+        let size = window.inner_size().to_logical::<f32>(window.scale_factor());
+        Some(Rect::from_corners(min, min + Vec2::new(size.width, size.height)))
+    }
+
+    fn start_drag(&self, winit_windows: &WinitWindows, entity: Entity, _parent: Entity) {
+        let Some(window) = winit_windows.get_window(entity) else {
+            return;
+        };
+        if let Err(e) = window.drag_window() {
+            log_warn!(self.shared.log_level(), "{e}");
+        }
+    }
+}
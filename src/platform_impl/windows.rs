@@ -1,18 +1,23 @@
-use crate::{ParentWindow, UnInitializeWindow};
-use bevy::app::{App, Plugin, Update};
-use bevy::log::error;
-use bevy::math::IVec2;
+use crate::backend::SharedState;
+use crate::interaction::InteractionKind;
+use crate::tear_off::distance_outside;
+use crate::util::{log_debug, log_error, log_warn};
+use crate::{ChildWindowBackend, ChildWindowLogLevel, ChildWindowMinimized, Containment, ChildWindowNativeResources, ChildWindowSystems, ModalBlockedWindows, ParentWindow};
+use bevy::app::{App, Plugin, PostUpdate, Update};
+use bevy::math::{Rect, Vec2};
 use bevy::prelude::*;
 use bevy::winit::WinitWindows;
 use std::collections::BTreeMap;
 use std::ffi::c_void;
-use std::sync::Mutex;
-use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM};
-use windows::Win32::UI::WindowsAndMessaging::{CallWindowProcW, GetWindowRect, SetWindowLongPtrW, GWLP_WNDPROC, SM_CYCAPTION, WM_MOVING, WNDPROC};
+use std::sync::{Arc, Mutex};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, POINT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{ClientToScreen, ScreenToClient};
+use windows::Win32::UI::WindowsAndMessaging::{BringWindowToTop, CallWindowProcW, GetCursorPos, GetWindowLongPtrW, GetWindowRect, SetWindowLongPtrW, SetWindowPos, GA_PARENT, GWLP_WNDPROC, GWL_STYLE, HTBORDER, HTBOTTOM, HTBOTTOMLEFT, HTBOTTOMRIGHT, HTCAPTION, HTCLIENT, HTLEFT, HTRIGHT, HTTOP, HTTOPLEFT, HTTOPRIGHT, MA_NOACTIVATE, SC_MINIMIZE, SC_MOVE, SC_SIZE, SWP_FRAMECHANGED, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, WM_ENTERSIZEMOVE, WM_EXITSIZEMOVE, WM_LBUTTONDOWN, WM_MOUSEACTIVATE, WM_MOVING, WM_NCDESTROY, WM_NCHITTEST, WM_NCLBUTTONDOWN, WM_SIZING, WM_SYSCOMMAND, WNDPROC, WS_CHILD};
 use windows::Win32::UI::WindowsAndMessaging::{
-    DefWindowProcW, GetAncestor, GetClientRect,
-    GetSystemMetrics, SetParent,
+    AdjustWindowRectEx, DefWindowProcW, GetAncestor, GetClientRect,
+    SendMessageW, SetParent, GWL_EXSTYLE, WINDOW_EX_STYLE, WINDOW_STYLE,
 };
+use windows::Win32::UI::Input::KeyboardAndMouse::ReleaseCapture;
 #[allow(deprecated)]
 use winit::raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
 
@@ -22,151 +27,619 @@ pub struct ChildWindowPlugin;
 
 impl Plugin for ChildWindowPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, convert_to_child_window.run_if(any_with_component::<UnInitializeWindow>));
+        app
+            .add_systems(Update, take_native_minimize_requests)
+            .add_systems(PostUpdate, sync_modal_blocked_windows
+                .run_if(resource_changed::<ModalBlockedWindows>)
+                .in_set(ChildWindowSystems::Sync));
     }
 }
 
-fn convert_to_child_window(
-    mut commands: Commands,
-    winit_windows: NonSend<WinitWindows>,
-    windows: Query<(Entity, &ParentWindow), (With<Window>, With<UnInitializeWindow>)>,
-) {
-    for (entity, ParentWindow(parent_entity)) in windows.iter() {
-        let Some(child) = winit_windows.get_window(entity) else {
-            continue;
-        };
-        let Some(parent) = winit_windows.get_window(*parent_entity) else {
-            continue;
-        };
+/// Attaches child windows with `SetParent` and hooks their window procedure with [`window_move_proc`].
+pub(crate) struct NativeBackend {
+    shared: Arc<SharedState>,
+}
 
-        let Some(child_window_handle) = obtain_window_handle(child) else {
-            continue;
+impl NativeBackend {
+    pub(crate) fn new(shared: Arc<SharedState>) -> Self {
+        Self { shared }
+    }
+}
+
+impl ChildWindowBackend for NativeBackend {
+    fn attach(&self, winit_windows: &WinitWindows, entity: Entity, _window: &Window, parent: Entity) -> bool {
+        let Some(child_window_handle) = winit_windows.get_window(entity).and_then(|window| obtain_window_handle(window)) else {
+            return false;
         };
-        let Some(parent_window_handle) = obtain_window_handle(parent) else {
-            continue;
+        let Some(parent_window_handle) = winit_windows.get_window(parent).and_then(|window| obtain_window_handle(window)) else {
+            return false;
         };
         unsafe {
             match SetParent(child_window_handle, Some(parent_window_handle)) {
                 Ok(_) => {
                     #[allow(clippy::fn_to_numeric_cast)]
                     let default_window_proc = SetWindowLongPtrW(child_window_handle, GWLP_WNDPROC, window_move_proc as isize);
-                    HOOKS.lock().unwrap().insert(
-                        child_window_handle.0 as isize,
+                    HOOKS.lock().unwrap().insert(child_window_handle.0 as isize, Hook {
                         #[allow(clippy::missing_transmute_annotations)]
-                        Some(std::mem::transmute(default_window_proc)),
-                    );
-                    commands.entity(entity).remove::<UnInitializeWindow>();
+                        default_window_proc: Some(std::mem::transmute(default_window_proc)),
+                        entity,
+                        shared: self.shared.clone(),
+                    });
+                    true
                 }
-                Err(e) => error!("{e}")
+                Err(e) => {
+                    log_error!(self.shared.log_level(), "{e}");
+                    false
+                }
+            }
+        }
+    }
+
+    /// Windows destroyed without removing [`ParentWindow`] first are released by `WM_NCDESTROY` instead.
+    fn detach(&self, winit_windows: &WinitWindows, entity: Entity) {
+        let hwnd = HOOKS
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(_, hook)| hook.entity == entity && Arc::ptr_eq(&hook.shared, &self.shared))
+            .map(|(hwnd, _)| *hwnd);
+        let Some(hwnd) = hwnd else {
+            return;
+        };
+        let hwnd = HWND(hwnd as *mut c_void);
+        unsafe {
+            release_child_window(hwnd);
+            if winit_windows.get_window(entity).is_some() {
+                detach_child_window(hwnd, self.shared.log_level());
+                log_debug!(self.shared.log_level(), "Detached the child window {entity}");
             }
         }
     }
+
+    /// Sets the window rect of the child window so that its client area becomes `rect`.
+    fn set_rect(&self, winit_windows: &WinitWindows, entity: Entity, _parent: Entity, rect: Rect) {
+        let Some(window) = winit_windows.get_window(entity) else {
+            return;
+        };
+        let Some(hwnd) = obtain_window_handle(window) else {
+            return;
+        };
+        let rect = Rect::from_corners(rect.min * window.scale_factor() as f32, rect.max * window.scale_factor() as f32);
+        unsafe {
+            let Some(parent_client_rect) = obtain_parent_client_rect(hwnd) else {
+                return;
+            };
+            let window_rect = client_to_window_rect(hwnd, &RECT {
+                left: parent_client_rect.left + rect.min.x.round() as i32,
+                top: parent_client_rect.top + rect.min.y.round() as i32,
+                right: parent_client_rect.left + rect.max.x.round() as i32,
+                bottom: parent_client_rect.top + rect.max.y.round() as i32,
+            });
+            let mut position = POINT {
+                x: window_rect.left,
+                y: window_rect.top,
+            };
+            // The coordinates of a window with `WS_CHILD` are relative to the client area of its parent.
+            if GetWindowLongPtrW(hwnd, GWL_STYLE) as u32 & WS_CHILD.0 != 0 {
+                let parent = GetAncestor(hwnd, GA_PARENT);
+                if !ScreenToClient(parent, &mut position).as_bool() {
+                    return;
+                }
+            }
+            if let Err(e) = SetWindowPos(
+                hwnd,
+                None,
+                position.x,
+                position.y,
+                window_rect.right - window_rect.left,
+                window_rect.bottom - window_rect.top,
+                SWP_NOZORDER | SWP_NOACTIVATE,
+            ) {
+                log_error!(self.shared.log_level(), "{e}");
+            }
+        }
+    }
+
+    fn raise(&self, winit_windows: &WinitWindows, entity: Entity) {
+        let Some(hwnd) = winit_windows.get_window(entity).and_then(|window| obtain_window_handle(window)) else {
+            return;
+        };
+        unsafe {
+            if let Err(e) = BringWindowToTop(hwnd) {
+                log_error!(self.shared.log_level(), "{e}");
+            }
+        }
+    }
+
+    fn focus(&self, winit_windows: &WinitWindows, entity: Entity) {
+        self.raise(winit_windows, entity);
+        if let Some(window) = winit_windows.get_window(entity) {
+            window.focus_window();
+        }
+    }
+
+    fn query_rect(&self, winit_windows: &WinitWindows, entity: Entity, _parent: Entity) -> Option<Rect> {
+        let window = winit_windows.get_window(entity)?;
+        let hwnd = obtain_window_handle(window)?;
+        unsafe {
+            let parent_client_rect = obtain_parent_client_rect(hwnd)?;
+            let mut window_rect = RECT::default();
+            GetWindowRect(hwnd, &mut window_rect).ok()?;
+            let client_rect = window_to_client_rect(hwnd, &window_rect);
+            let scale_factor = window.scale_factor() as f32;
+            Some(Rect::new(
+                (client_rect.left - parent_client_rect.left) as f32 / scale_factor,
+                (client_rect.top - parent_client_rect.top) as f32 / scale_factor,
+                (client_rect.right - parent_client_rect.left) as f32 / scale_factor,
+                (client_rect.bottom - parent_client_rect.top) as f32 / scale_factor,
+            ))
+        }
+    }
+
+    /// The move started by `winit` is clamped by [`window_move_proc`] like the one started by the user.
+    fn start_drag(&self, winit_windows: &WinitWindows, entity: Entity, _parent: Entity) {
+        let Some(window) = winit_windows.get_window(entity) else {
+            return;
+        };
+        if let Err(e) = window.drag_window() {
+            log_warn!(self.shared.log_level(), "{e}");
+        }
+    }
+
+    /// `winit` updates the window styles of the child window when the decorations change,
+    /// so the frame is recalculated to reflect them inside the parent window.
+    fn apply_decorations(&self, winit_windows: &WinitWindows, entity: Entity, _window: &Window) {
+        let Some(hwnd) = winit_windows.get_window(entity).and_then(|window| obtain_window_handle(window)) else {
+            return;
+        };
+        unsafe {
+            if let Err(e) = SetWindowPos(
+                hwnd,
+                None,
+                0,
+                0,
+                0,
+                0,
+                SWP_FRAMECHANGED | SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE,
+            ) {
+                log_error!(self.shared.log_level(), "{e}");
+            }
+        }
+    }
+
+    fn native_resources(&self) -> ChildWindowNativeResources {
+        ChildWindowNativeResources {
+            child_windows: HOOKS
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|hook| Arc::ptr_eq(&hook.shared, &self.shared))
+                .count(),
+            event_monitors: 0,
+        }
+    }
+}
+
+/// Restores the original window procedure and forgets the child window.
+unsafe fn release_child_window(hwnd: HWND) {
+    let key = hwnd.0 as isize;
+    let hook = HOOKS.lock().unwrap().remove(&key);
+    if let Some(default_window_proc) = hook.and_then(|hook| hook.default_window_proc) {
+        #[allow(clippy::fn_to_numeric_cast)]
+        SetWindowLongPtrW(hwnd, GWLP_WNDPROC, default_window_proc as isize);
+    }
+    MODAL_BLOCKED_WINDOWS.lock().unwrap().retain(|hwnd| *hwnd != key);
+    SIZE_MOVES.lock().unwrap().remove(&key);
+}
+
+unsafe fn detach_child_window(hwnd: HWND, log_level: ChildWindowLogLevel) {
+    if let Err(e) = SetParent(hwnd, None) {
+        log_error!(log_level, "{e}");
+    }
+}
+
+/// Minimizing a child window from its title bar would iconify it inside the parent's client area,
+/// so the request is forwarded to [`ChildWindowMinimized`] instead.
+fn take_native_minimize_requests(
+    mut commands: Commands,
+    winit_windows: NonSend<WinitWindows>,
+    windows: Query<Entity, (With<Window>, With<ParentWindow>)>,
+) {
+    let requests = std::mem::take(&mut *MINIMIZE_REQUESTS.lock().unwrap());
+    if requests.is_empty() {
+        return;
+    }
+    for entity in windows.iter() {
+        let Some(hwnd) = winit_windows.get_window(entity).and_then(|window| obtain_window_handle(window)) else {
+            continue;
+        };
+        if requests.contains(&(hwnd.0 as isize)) {
+            commands.entity(entity).insert(ChildWindowMinimized);
+        }
+    }
+}
+
+fn sync_modal_blocked_windows(
+    winit_windows: NonSend<WinitWindows>,
+    blocked_windows: Res<ModalBlockedWindows>,
+) {
+    let blocked = blocked_windows
+        .iter()
+        .filter_map(|entity| winit_windows.get_window(entity).and_then(|window| obtain_window_handle(window)))
+        .map(|hwnd| hwnd.0 as isize)
+        .collect();
+    *MODAL_BLOCKED_WINDOWS.lock().unwrap() = blocked;
 }
 
-static HOOKS: Mutex<BTreeMap<isize, WNDPROC>> = Mutex::new(BTreeMap::new());
+/// The child windows hooked by [`window_move_proc`], keyed by their `HWND`.
+static HOOKS: Mutex<BTreeMap<isize, Hook>> = Mutex::new(BTreeMap::new());
+
+struct Hook {
+    default_window_proc: WNDPROC,
+    entity: Entity,
+    /// The state of the app that owns the child window.
+    shared: Arc<SharedState>,
+}
+
+static MINIMIZE_REQUESTS: Mutex<Vec<isize>> = Mutex::new(Vec::new());
+
+static MODAL_BLOCKED_WINDOWS: Mutex<Vec<isize>> = Mutex::new(Vec::new());
+
+static SIZE_MOVES: Mutex<BTreeMap<isize, SizeMove>> = Mutex::new(BTreeMap::new());
+
+/// The state of the modal move or resize loop of a child window.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum SizeMove {
+    Entered,
+    Moving,
+    Sizing,
+}
 
 unsafe extern "system" fn window_move_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    let Some((entity, shared)) = hooked_window(hwnd) else {
+        return call_default_hook(hwnd, msg, wparam, lparam);
+    };
     match msg {
         WM_MOVING => {
-            let Some(parent_window_rect) = obtain_parent_window_rect(hwnd) else {
+            let Some(parent_client_rect) = obtain_parent_client_rect(hwnd) else {
                 return call_default_hook(hwnd, msg, wparam, lparam);
             };
-            let Some(window_size) = calc_window_size(hwnd) else {
-                return call_default_hook(hwnd, msg, wparam, lparam);
-            };
-            let Some(frame_size) = calc_frame_size(hwnd, window_size) else {
+            let Some(border_width) = calc_border_width(hwnd) else {
                 return call_default_hook(hwnd, msg, wparam, lparam);
             };
 
             let mut drag_rect = *(lparam.0 as *mut RECT);
-            adjust_drag_rect_width(&mut drag_rect, &parent_window_rect, window_size.x, frame_size.x);
-            adjust_drag_rect_height(&mut drag_rect, &parent_window_rect, window_size.y, frame_size.y);
+            notify_drag_overshoot(entity, &shared, &parent_client_rect, &drag_rect);
+            if is_contained(entity, &shared) {
+                clamp_drag_rect(&mut drag_rect, &parent_client_rect, border_width);
+            }
             *(lparam.0 as *mut RECT) = drag_rect;
+            notify_size_move(hwnd, entity, &shared, SizeMove::Moving, &drag_rect);
 
             call_default_hook(hwnd, msg, wparam, lparam)
         }
+        WM_SIZING => {
+            if let Some(parent_client_rect) = obtain_parent_client_rect(hwnd).filter(|_| is_contained(entity, &shared)) {
+                let drag_rect = &mut *(lparam.0 as *mut RECT);
+                drag_rect.left = drag_rect.left.max(parent_client_rect.left);
+                drag_rect.top = drag_rect.top.max(parent_client_rect.top);
+                drag_rect.right = drag_rect.right.min(parent_client_rect.right);
+                drag_rect.bottom = drag_rect.bottom.min(parent_client_rect.bottom);
+            }
+            notify_size_move(hwnd, entity, &shared, SizeMove::Sizing, &*(lparam.0 as *const RECT));
+            call_default_hook(hwnd, msg, wparam, lparam)
+        }
+        WM_ENTERSIZEMOVE => {
+            SIZE_MOVES.lock().unwrap().insert(hwnd.0 as isize, SizeMove::Entered);
+            call_default_hook(hwnd, msg, wparam, lparam)
+        }
+        WM_EXITSIZEMOVE => {
+            let state = SIZE_MOVES.lock().unwrap().remove(&(hwnd.0 as isize));
+            let kind = match state {
+                Some(SizeMove::Moving) => Some(InteractionKind::DragEnded),
+                Some(SizeMove::Sizing) => Some(InteractionKind::ResizeEnded),
+                _ => None,
+            };
+            let mut window_rect = RECT::default();
+            if let Some(kind) = kind {
+                if GetWindowRect(hwnd, &mut window_rect).is_ok() {
+                    push_size_move_interaction(hwnd, entity, &shared, kind, &window_rect);
+                }
+            }
+            call_default_hook(hwnd, msg, wparam, lparam)
+        }
+        WM_NCHITTEST => {
+            let hit = call_default_hook(hwnd, msg, wparam, lparam);
+            let hit = if hit.0 == HTCLIENT as isize {
+                hit_test_client_area(hwnd, entity, &shared, lparam)
+                    .map(|code| LRESULT(code as isize))
+                    .unwrap_or(hit)
+            } else {
+                hit
+            };
+            // Hides the resize cursor on the borders while the size is locked.
+            if is_resize_hit(hit) && shared.window_lock(entity).size {
+                return LRESULT(HTBORDER as isize);
+            }
+            hit
+        }
+        WM_SYSCOMMAND if (wparam.0 as u32 & 0xFFF0) == SC_MOVE && shared.window_lock(entity).position => {
+            LRESULT(0)
+        }
+        WM_SYSCOMMAND if (wparam.0 as u32 & 0xFFF0) == SC_SIZE && shared.window_lock(entity).size => {
+            LRESULT(0)
+        }
+        WM_SYSCOMMAND if (wparam.0 as u32 & 0xFFF0) == SC_MINIMIZE => {
+            MINIMIZE_REQUESTS.lock().unwrap().push(hwnd.0 as isize);
+            LRESULT(0)
+        }
+        // The click is still delivered so that the modal window can be raised from the input events.
+        WM_MOUSEACTIVATE if is_modal_blocked(hwnd) => LRESULT(MA_NOACTIVATE as isize),
+        WM_MOUSEACTIVATE => {
+            if shared.window_settings(entity).raise_on_click {
+                if let Err(e) = BringWindowToTop(hwnd) {
+                    log_warn!(shared.log_level(), "{e}");
+                }
+            }
+            call_default_hook(hwnd, msg, wparam, lparam)
+        }
+        WM_NCLBUTTONDOWN if is_modal_blocked(hwnd) => LRESULT(0),
+        WM_LBUTTONDOWN => {
+            let result = call_default_hook(hwnd, msg, wparam, lparam);
+            start_background_drag(hwnd, entity, &shared, lparam);
+            result
+        }
+        WM_NCDESTROY => {
+            let result = call_default_hook(hwnd, msg, wparam, lparam);
+            release_child_window(hwnd);
+            result
+        }
         _ => call_default_hook(hwnd, msg, wparam, lparam),
     }
 }
 
-unsafe fn obtain_parent_window_rect(hwnd: HWND) -> Option<RECT> {
-    let parent = GetAncestor(
-        hwnd,
-        windows::Win32::UI::WindowsAndMessaging::GET_ANCESTOR_FLAGS(1),
+/// Reports the move or resize of the child window to `rect` in screen coordinates.
+///
+/// The first one after `WM_ENTERSIZEMOVE` also reports the start of the interaction.
+unsafe fn notify_size_move(hwnd: HWND, entity: Entity, shared: &SharedState, current: SizeMove, rect: &RECT) {
+    let Some(previous) = SIZE_MOVES
+        .lock()
+        .unwrap()
+        .get_mut(&(hwnd.0 as isize))
+        .map(|state| std::mem::replace(state, current))
+    else {
+        return;
+    };
+    let (started, updated) = match current {
+        SizeMove::Sizing => (InteractionKind::ResizeStarted, InteractionKind::Resized),
+        _ => (InteractionKind::DragStarted, InteractionKind::Dragged),
+    };
+    if previous == SizeMove::Entered {
+        push_size_move_interaction(hwnd, entity, shared, started, rect);
+    }
+    push_size_move_interaction(hwnd, entity, shared, updated, rect);
+}
+
+/// `rect` is the window rect, which is reported as the client area.
+unsafe fn push_size_move_interaction(hwnd: HWND, entity: Entity, shared: &SharedState, kind: InteractionKind, rect: &RECT) {
+    let Some(parent_client_rect) = obtain_parent_client_rect(hwnd) else {
+        return;
+    };
+    let rect = window_to_client_rect(hwnd, rect);
+    let origin = Vec2::new(parent_client_rect.left as f32, parent_client_rect.top as f32);
+    shared.push_interaction_physical(entity, kind, Rect::new(
+        rect.left as f32 - origin.x,
+        rect.top as f32 - origin.y,
+        rect.right as f32 - origin.x,
+        rect.bottom as f32 - origin.y,
+    ));
+}
+
+/// Reports how far the cursor is outside the parent's client area, so that the child window can be torn off.
+unsafe fn notify_drag_overshoot(entity: Entity, shared: &SharedState, parent_client_rect: &RECT, drag_rect: &RECT) {
+    let mut cursor = POINT::default();
+    if GetCursorPos(&mut cursor).is_err() {
+        return;
+    }
+    let area = Rect::new(
+        parent_client_rect.left as f32,
+        parent_client_rect.top as f32,
+        parent_client_rect.right as f32,
+        parent_client_rect.bottom as f32,
     );
+    let distance = distance_outside(area, Vec2::new(cursor.x as f32, cursor.y as f32));
+    if 0. < distance {
+        shared.push_drag_overshoot_physical(entity, distance, IVec2::new(drag_rect.left, drag_rect.top));
+    }
+}
 
-    let mut parent_window_rect = RECT::default();
-    GetWindowRect(parent, &mut parent_window_rect).ok()?;
-    Some(parent_window_rect)
+/// Returns the entity of the hooked child window and the state of the app that owns it.
+fn hooked_window(hwnd: HWND) -> Option<(Entity, Arc<SharedState>)> {
+    HOOKS
+        .lock()
+        .unwrap()
+        .get(&(hwnd.0 as isize))
+        .map(|hook| (hook.entity, hook.shared.clone()))
 }
 
-unsafe fn calc_window_size(hwnd: HWND) -> Option<IVec2> {
-    let mut window_rect = RECT::default();
-    GetWindowRect(hwnd, &mut window_rect).ok()?;
-    Some(IVec2::new(
-        window_rect.right - window_rect.left,
-        window_rect.bottom - window_rect.top,
-    ))
+/// Returns the hit-test code if the point is on [`crate::ChildWindowResizeBorder`] or starts moving the child window.
+unsafe fn hit_test_client_area(hwnd: HWND, entity: Entity, shared: &SharedState, lparam: LPARAM) -> Option<u32> {
+    let mut point = POINT {
+        x: (lparam.0 & 0xFFFF) as i16 as i32,
+        y: ((lparam.0 >> 16) & 0xFFFF) as i16 as i32,
+    };
+    if !ScreenToClient(hwnd, &mut point).as_bool() {
+        return None;
+    }
+    let mut client_rect = RECT::default();
+    GetClientRect(hwnd, &mut client_rect).ok()?;
+    let position = Vec2::new(point.x as f32, point.y as f32);
+    let size = Vec2::new(
+        (client_rect.right - client_rect.left) as f32,
+        (client_rect.bottom - client_rect.top) as f32,
+    );
+    if let Some(dir) = shared.hit_test_resize_border_physical(entity, position, size) {
+        return Some(match (dir.left, dir.right, dir.top, dir.bottom) {
+            (true, _, true, _) => HTTOPLEFT,
+            (_, true, true, _) => HTTOPRIGHT,
+            (true, _, _, true) => HTBOTTOMLEFT,
+            (_, true, _, true) => HTBOTTOMRIGHT,
+            (true, ..) => HTLEFT,
+            (_, true, ..) => HTRIGHT,
+            (_, _, true, _) => HTTOP,
+            _ => HTBOTTOM,
+        });
+    }
+    // The rest of the background is moved on `WM_LBUTTONDOWN` instead, so that the click still reaches the app.
+    shared.drag_start_hit_physical(entity, position).starts_caption_drag().then_some(HTCAPTION)
 }
 
-unsafe fn calc_frame_size(
-    hwnd: HWND,
-    window_size: IVec2,
-) -> Option<IVec2> {
-    let mut window_client = RECT::default();
-    GetClientRect(hwnd, &mut window_client).ok()?;
+/// Moves the child window by [`Window::movable_by_window_background`] after the click has reached the app,
+/// by handing the press to the native move loop as if it were on the title bar.
+unsafe fn start_background_drag(hwnd: HWND, entity: Entity, shared: &SharedState, lparam: LPARAM) {
+    let mut point = POINT {
+        x: (lparam.0 & 0xFFFF) as i16 as i32,
+        y: ((lparam.0 >> 16) & 0xFFFF) as i16 as i32,
+    };
+    if !shared.drag_start_hit_physical(entity, Vec2::new(point.x as f32, point.y as f32)).starts_drag() {
+        return;
+    }
+    if !ClientToScreen(hwnd, &mut point).as_bool() {
+        return;
+    }
+    if let Err(e) = ReleaseCapture() {
+        log_warn!(shared.log_level(), "{e}");
+        return;
+    }
+    let position = (point.x as u16 as isize) | ((point.y as u16 as isize) << 16);
+    SendMessageW(hwnd, WM_NCLBUTTONDOWN, Some(WPARAM(HTCAPTION as usize)), Some(LPARAM(position)));
+}
 
-    let client_h = window_client.bottom - window_client.top;
-    let client_w = window_client.right - window_client.left;
-    Some(IVec2::new(
-        (window_size.x - client_w) / 2,
-        (window_size.y - client_h) / 2,
-    ))
+fn is_resize_hit(hit: LRESULT) -> bool {
+    [HTLEFT, HTRIGHT, HTTOP, HTTOPLEFT, HTTOPRIGHT, HTBOTTOM, HTBOTTOMLEFT, HTBOTTOMRIGHT]
+        .iter()
+        .any(|code| hit.0 == *code as isize)
 }
 
-unsafe fn adjust_drag_rect_width(
-    drag_rect: &mut RECT,
-    parent_window_rect: &RECT,
-    window_width: i32,
-    frame_width: i32,
-) {
-    let max_x = parent_window_rect.right - frame_width;
-    let min_x = parent_window_rect.left + frame_width;
+/// Returns true if the child window is kept inside the client area of its parent by [`crate::Containment`].
+fn is_contained(entity: Entity, shared: &SharedState) -> bool {
+    shared.window_settings(entity).containment == Containment::ClientArea
+}
+
+fn is_modal_blocked(hwnd: HWND) -> bool {
+    MODAL_BLOCKED_WINDOWS
+        .lock()
+        .unwrap()
+        .contains(&(hwnd.0 as isize))
+}
 
-    if max_x < drag_rect.right {
-        drag_rect.right = max_x;
-        drag_rect.left = drag_rect.left.min(drag_rect.right - window_width);
-    } else if drag_rect.left < min_x {
-        drag_rect.left = min_x;
-        drag_rect.right = drag_rect.right.max(drag_rect.left + window_width + frame_width);
+/// Returns the client area of the immediate parent window in screen coordinates.
+///
+/// The parent window may itself be a child window.
+unsafe fn obtain_parent_client_rect(hwnd: HWND) -> Option<RECT> {
+    let parent = GetAncestor(
+        hwnd,
+        windows::Win32::UI::WindowsAndMessaging::GET_ANCESTOR_FLAGS(1),
+    );
+    let mut client_rect = RECT::default();
+    GetClientRect(parent, &mut client_rect).ok()?;
+    let mut top_left = POINT {
+        x: client_rect.left,
+        y: client_rect.top,
+    };
+    let mut bottom_right = POINT {
+        x: client_rect.right,
+        y: client_rect.bottom,
+    };
+    if !ClientToScreen(parent, &mut top_left).as_bool() || !ClientToScreen(parent, &mut bottom_right).as_bool() {
+        return None;
     }
+    Some(RECT {
+        left: top_left.x,
+        top: top_left.y,
+        right: bottom_right.x,
+        bottom: bottom_right.y,
+    })
 }
 
-unsafe fn adjust_drag_rect_height(
-    drag_rect: &mut RECT,
-    parent_frame: &RECT,
-    window_height: i32,
-    frame_height: i32,
-) {
-    let title_bar_height = GetSystemMetrics(SM_CYCAPTION);
-    let min_y = parent_frame.top + title_bar_height + frame_height;
-    let max_y = parent_frame.bottom - frame_height;
+/// Returns the offsets of the window rect from the client area on each side for the current styles of the window.
+///
+/// `left` and `top` are negative, and `right` and `bottom` are positive.
+unsafe fn frame_insets(hwnd: HWND) -> RECT {
+    let style = WINDOW_STYLE(GetWindowLongPtrW(hwnd, GWL_STYLE) as u32);
+    let ex_style = WINDOW_EX_STYLE(GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32);
+    let mut insets = RECT::default();
+    if AdjustWindowRectEx(&mut insets, style, false, ex_style).is_err() {
+        return RECT::default();
+    }
+    insets
+}
 
-    if drag_rect.top < min_y {
-        drag_rect.top = min_y;
-        drag_rect.bottom = drag_rect.bottom.max(min_y + window_height);
-    } else if max_y < drag_rect.bottom {
-        drag_rect.bottom = max_y;
-        drag_rect.top = drag_rect.top.min(max_y - window_height);
+/// Converts the window rect of the window to its client area.
+unsafe fn window_to_client_rect(hwnd: HWND, rect: &RECT) -> RECT {
+    let insets = frame_insets(hwnd);
+    RECT {
+        left: rect.left - insets.left,
+        top: rect.top - insets.top,
+        right: rect.right - insets.right,
+        bottom: rect.bottom - insets.bottom,
     }
 }
 
+/// Converts the client area of the window to its window rect.
+unsafe fn client_to_window_rect(hwnd: HWND, rect: &RECT) -> RECT {
+    let insets = frame_insets(hwnd);
+    RECT {
+        left: rect.left + insets.left,
+        top: rect.top + insets.top,
+        right: rect.right + insets.right,
+        bottom: rect.bottom + insets.bottom,
+    }
+}
+
+/// Returns the width of the invisible resize border on each side of the window.
+unsafe fn calc_border_width(hwnd: HWND) -> Option<i32> {
+    let mut window_rect = RECT::default();
+    GetWindowRect(hwnd, &mut window_rect).ok()?;
+    let mut client_rect = RECT::default();
+    GetClientRect(hwnd, &mut client_rect).ok()?;
+    let window_width = window_rect.right - window_rect.left;
+    let client_width = client_rect.right - client_rect.left;
+    Some((window_width - client_width) / 2)
+}
+
+/// Keeps the window inside the client area of its immediate parent window.
+///
+/// Only the invisible resize borders on the left, right and bottom may protrude from the client area.
+fn clamp_drag_rect(
+    drag_rect: &mut RECT,
+    parent_client_rect: &RECT,
+    border_width: i32,
+) {
+    let width = drag_rect.right - drag_rect.left;
+    let height = drag_rect.bottom - drag_rect.top;
+    let max_x = parent_client_rect.right + border_width - width;
+    let min_x = parent_client_rect.left - border_width;
+    let max_y = parent_client_rect.bottom + border_width - height;
+    let min_y = parent_client_rect.top;
+    let left = drag_rect.left.min(max_x).max(min_x);
+    let top = drag_rect.top.min(max_y).max(min_y);
+    *drag_rect = RECT {
+        left,
+        top,
+        right: left + width,
+        bottom: top + height,
+    };
+}
+
 unsafe fn call_default_hook(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
-    if let Some(hook) = HOOKS
-        .try_lock()
-        .ok()
-        .and_then(|hooks| hooks.get(&(hwnd.0 as isize)).copied())
-    {
+    // The procedure is copied out so that the lock isn't held while it runs.
+    let hook = HOOKS
+        .lock()
+        .unwrap()
+        .get(&(hwnd.0 as isize))
+        .map(|hook| hook.default_window_proc);
+    if let Some(hook) = hook {
         CallWindowProcW(hook, hwnd, msg, wparam, lparam)
     } else {
         DefWindowProcW(hwnd, msg, wparam, lparam)
@@ -0,0 +1,357 @@
+use crate::backend::ActiveBackend;
+use crate::{ChildWindowSystems, ParentWindow, UnInitializeWindow};
+use bevy::app::{App, Plugin, PostUpdate, PreUpdate};
+use bevy::input::mouse::MouseButtonInput;
+use bevy::input::{ButtonState, InputSystem};
+use bevy::math::{Rect, Vec2};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Commands, Component, Entity, EventReader, IntoScheduleConfigs, NonSend, ParamSet, Query, Reflect, ReflectComponent, Res, Window, Without};
+use bevy::window::WindowFocused;
+use bevy::winit::WinitWindows;
+
+pub(crate) struct ChildWindowPopupPlugin;
+
+impl Plugin for ChildWindowPopupPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<ChildWindowPopup>()
+            .register_type::<PopupPlacement>()
+            .add_systems(PreUpdate, close_popups.after(InputSystem))
+            .add_systems(PostUpdate, place_popups.in_set(ChildWindowSystems::Layout));
+    }
+}
+
+/// Makes the child window a popup, such as a context menu or a tooltip, placed next to an anchor rect.
+///
+/// The popup is placed on [`ChildWindowPopup::placement`] side of [`ChildWindowPopup::anchor`].
+/// If it overflows the client area of the parent window, it is flipped to the opposite side and/or slid along the edge,
+/// depending on [`ChildWindowPopup::flip`] and [`ChildWindowPopup::slide`].
+///
+/// The popup is despawned on a click outside of it or when it loses the focus, if enabled.
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::window::PrimaryWindow;
+/// use bevy_child_window::prelude::*;
+///
+/// fn open_context_menu(
+///     mut commands: Commands,
+///     parent: Query<(Entity, &Window), With<PrimaryWindow>>,
+///     mouse: Res<ButtonInput<MouseButton>>,
+/// ){
+///     let (entity, window) = parent.single().expect("Parent not found");
+///     let Some(cursor) = window.cursor_position() else {
+///         return;
+///     };
+///     if mouse.just_pressed(MouseButton::Right) {
+///         commands.spawn((
+///             ParentWindow(entity),
+///             ChildWindowPopup::at_point(cursor),
+///             Window {
+///                 decorations: false,
+///                 resolution: (200., 300.).into(),
+///                 ..default()
+///             },
+///         ));
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Copy, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct ChildWindowPopup {
+    /// The rect the popup is placed next to, in logical pixels relative to the top-left corner of the parent window's client area.
+    pub anchor: Rect,
+    /// The preferred side of the anchor.
+    pub placement: PopupPlacement,
+    /// Whether the popup is moved to the opposite side of the anchor if it overflows the parent window.
+    pub flip: bool,
+    /// Whether the popup is slid along the edges of the parent window to stay inside it.
+    pub slide: bool,
+    /// Whether the popup is despawned when the left or right mouse button is pressed outside of it.
+    pub close_on_outside_click: bool,
+    /// Whether the popup is despawned when it loses the focus.
+    pub close_on_focus_loss: bool,
+}
+
+impl ChildWindowPopup {
+    /// Creates the popup placed below `anchor` that flips, slides and closes on an outside click.
+    pub const fn anchored_to(anchor: Rect) -> Self {
+        Self {
+            anchor,
+            placement: PopupPlacement::Below,
+            flip: true,
+            slide: true,
+            close_on_outside_click: true,
+            close_on_focus_loss: false,
+        }
+    }
+
+    /// Creates the popup whose top-left corner is at `point`, such as a context menu opened at the cursor.
+    pub const fn at_point(point: Vec2) -> Self {
+        Self::anchored_to(Rect {
+            min: point,
+            max: point,
+        })
+    }
+
+    /// Returns the rect of the popup of `size` in the parent window's client area of `parent_size`.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy::math::{Rect, Vec2};
+    /// use bevy_child_window::{ChildWindowPopup, PopupPlacement};
+    ///
+    /// let parent_size = Vec2::new(800., 600.);
+    /// let size = Vec2::new(200., 100.);
+    /// let popup = ChildWindowPopup::anchored_to(Rect::new(100., 100., 200., 130.));
+    /// assert_eq!(popup.place(size, parent_size), Rect::new(100., 130., 300., 230.));
+    ///
+    /// // Flipped above the anchor because there is no room below it.
+    /// let popup = ChildWindowPopup::anchored_to(Rect::new(100., 550., 200., 580.));
+    /// assert_eq!(popup.place(size, parent_size), Rect::new(100., 450., 300., 550.));
+    ///
+    /// // Slid to the left to stay inside the parent window.
+    /// let popup = ChildWindowPopup::anchored_to(Rect::new(700., 100., 750., 130.));
+    /// assert_eq!(popup.place(size, parent_size), Rect::new(600., 130., 800., 230.));
+    ///
+    /// // Neither flipped nor slid.
+    /// let popup = ChildWindowPopup {
+    ///     placement: PopupPlacement::Right,
+    ///     flip: false,
+    ///     slide: false,
+    ///     ..ChildWindowPopup::anchored_to(Rect::new(700., 100., 750., 130.))
+    /// };
+    /// assert_eq!(popup.place(size, parent_size), Rect::new(750., 100., 950., 200.));
+    /// ```
+    pub fn place(&self, size: Vec2, parent_size: Vec2) -> Rect {
+        let client_area = Rect::from_corners(Vec2::ZERO, parent_size);
+        let mut rect = placed_rect(self.anchor, size, self.placement);
+        if self.flip && !contains_rect(client_area, rect) {
+            let flipped = placed_rect(self.anchor, size, self.placement.opposite());
+            if overflow(client_area, flipped) < overflow(client_area, rect) {
+                rect = flipped;
+            }
+        }
+        if self.slide {
+            let min = rect.min.min(parent_size - size).max(Vec2::ZERO);
+            rect = Rect::from_corners(min, min + size);
+        }
+        rect
+    }
+}
+
+/// The side of [`ChildWindowPopup::anchor`] where the popup is placed.
+#[derive(Reflect, Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum PopupPlacement {
+    /// Below the anchor, aligned to its left edge.
+    #[default]
+    Below,
+    /// Above the anchor, aligned to its left edge.
+    Above,
+    /// On the left of the anchor, aligned to its top edge.
+    Left,
+    /// On the right of the anchor, aligned to its top edge.
+    Right,
+}
+
+impl PopupPlacement {
+    /// Returns the placement on the opposite side.
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::Below => Self::Above,
+            Self::Above => Self::Below,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+}
+
+fn placed_rect(anchor: Rect, size: Vec2, placement: PopupPlacement) -> Rect {
+    let min = match placement {
+        PopupPlacement::Below => Vec2::new(anchor.min.x, anchor.max.y),
+        PopupPlacement::Above => Vec2::new(anchor.min.x, anchor.min.y - size.y),
+        PopupPlacement::Left => Vec2::new(anchor.min.x - size.x, anchor.min.y),
+        PopupPlacement::Right => Vec2::new(anchor.max.x, anchor.min.y),
+    };
+    Rect::from_corners(min, min + size)
+}
+
+fn contains_rect(outer: Rect, inner: Rect) -> bool {
+    outer.min.cmple(inner.min).all() && inner.max.cmple(outer.max).all()
+}
+
+/// Returns the area of `rect` outside of `client_area`.
+fn overflow(client_area: Rect, rect: Rect) -> f32 {
+    let size = rect.size();
+    let visible = rect.intersect(client_area);
+    let visible = if visible.is_empty() { 0. } else { visible.width() * visible.height() };
+    size.x * size.y - visible
+}
+
+/// The rect applied to the popup and the size of the parent window at that time.
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+struct PlacedPopup(Rect, Vec2);
+
+fn place_popups(
+    mut commands: Commands,
+    mut windows: ParamSet<(
+        Query<(Entity, &Window)>,
+        Query<(Entity, &ChildWindowPopup, &ParentWindow, &Window, Option<&PlacedPopup>), Without<UnInitializeWindow>>,
+    )>,
+    backend: Res<ActiveBackend>,
+    winit_windows: NonSend<WinitWindows>,
+) {
+    let window_sizes = windows
+        .p0()
+        .iter()
+        .map(|(entity, window)| (entity, window.size()))
+        .collect::<HashMap<_, _>>();
+    for (entity, popup, ParentWindow(parent), window, placed) in windows.p1().iter() {
+        let Some(parent_size) = window_sizes.get(parent).copied() else {
+            continue;
+        };
+        // The size is the client area the same as the rect of `set_rect`, so placing the popup doesn't change it.
+        let state = PlacedPopup(popup.place(window.size(), parent_size), parent_size);
+        if placed == Some(&state) {
+            continue;
+        }
+        backend.set_rect(&winit_windows, entity, *parent, state.0);
+        commands.entity(entity).insert(state);
+    }
+}
+
+fn close_popups(
+    mut commands: Commands,
+    mut mouse_buttons: EventReader<MouseButtonInput>,
+    mut focused: EventReader<WindowFocused>,
+    popups: Query<(Entity, &ChildWindowPopup)>,
+    parents: Query<&ParentWindow>,
+) {
+    let pressed_windows = mouse_buttons
+        .read()
+        .filter(|e| e.state == ButtonState::Pressed)
+        .map(|e| e.window)
+        .collect::<Vec<_>>();
+    let blurred_windows = focused
+        .read()
+        .filter(|e| !e.focused)
+        .map(|e| e.window)
+        .collect::<Vec<_>>();
+    for (entity, popup) in popups.iter() {
+        let clicked_outside = popup.close_on_outside_click && pressed_windows
+            .iter()
+            .any(|window| !is_self_or_descendant(*window, entity, &parents));
+        let lost_focus = popup.close_on_focus_loss && blurred_windows.contains(&entity);
+        if clicked_outside || lost_focus {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Returns true if `window` is `popup` itself or one of its nested child windows, such as a submenu.
+fn is_self_or_descendant(window: Entity, popup: Entity, parents: &Query<&ParentWindow>) -> bool {
+    let mut current = window;
+    for _ in 0..=parents.iter().len() {
+        if current == popup {
+            return true;
+        }
+        let Ok(ParentWindow(parent)) = parents.get(current) else {
+            return false;
+        };
+        current = *parent;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::{mock_app, MockBackend};
+    use std::sync::Arc;
+
+    const PARENT_SIZE: Vec2 = Vec2::new(800., 600.);
+    const SIZE: Vec2 = Vec2::new(200., 100.);
+
+    #[test]
+    fn placed_on_preferred_side_if_it_fits() {
+        for (placement, expected) in [
+            (PopupPlacement::Below, Rect::new(300., 330., 500., 430.)),
+            (PopupPlacement::Above, Rect::new(300., 200., 500., 300.)),
+            (PopupPlacement::Left, Rect::new(100., 300., 300., 400.)),
+            (PopupPlacement::Right, Rect::new(350., 300., 550., 400.)),
+        ] {
+            let popup = ChildWindowPopup {
+                placement,
+                ..ChildWindowPopup::anchored_to(Rect::new(300., 300., 350., 330.))
+            };
+            assert_eq!(popup.place(SIZE, PARENT_SIZE), expected, "{placement:?}");
+        }
+    }
+
+    #[test]
+    fn flipped_if_opposite_side_overflows_less() {
+        let popup = ChildWindowPopup {
+            placement: PopupPlacement::Left,
+            slide: false,
+            ..ChildWindowPopup::anchored_to(Rect::new(50., 300., 100., 330.))
+        };
+        assert_eq!(popup.place(SIZE, PARENT_SIZE), Rect::new(100., 300., 300., 400.));
+    }
+
+    #[test]
+    fn not_flipped_if_opposite_side_overflows_more() {
+        let popup = ChildWindowPopup {
+            slide: false,
+            ..ChildWindowPopup::anchored_to(Rect::new(100., 20., 200., 550.))
+        };
+        assert_eq!(popup.place(SIZE, PARENT_SIZE), Rect::new(100., 550., 300., 650.));
+    }
+
+    #[test]
+    fn slid_inside_parent_window() {
+        let popup = ChildWindowPopup {
+            flip: false,
+            ..ChildWindowPopup::anchored_to(Rect::new(-50., 560., 0., 590.))
+        };
+        assert_eq!(popup.place(SIZE, PARENT_SIZE), Rect::new(0., 500., 200., 600.));
+    }
+
+    #[test]
+    fn slid_to_top_left_if_larger_than_parent_window() {
+        let popup = ChildWindowPopup::at_point(Vec2::new(100., 100.));
+        assert_eq!(popup.place(Vec2::new(900., 700.), PARENT_SIZE), Rect::new(0., 0., 900., 700.));
+    }
+
+    #[test]
+    fn overflows_if_neither_flipped_nor_slid() {
+        let popup = ChildWindowPopup {
+            flip: false,
+            slide: false,
+            ..ChildWindowPopup::anchored_to(Rect::new(100., 550., 200., 580.))
+        };
+        assert_eq!(popup.place(SIZE, PARENT_SIZE), Rect::new(100., 580., 300., 680.));
+    }
+
+    #[test]
+    fn second_layout_keeps_rect() {
+        let backend = Arc::new(MockBackend::default());
+        let mut app = mock_app(backend.clone());
+        let parent = app.world_mut().spawn(Window::default()).id();
+        let mut window = Window::default();
+        window.resolution.set(SIZE.x, SIZE.y);
+        let popup = app
+            .world_mut()
+            .spawn((window, ParentWindow(parent), ChildWindowPopup::anchored_to(Rect::new(100., 650., 200., 680.))))
+            .id();
+        app.update();
+        let rect = backend.rects.lock().unwrap()[&popup];
+        assert_eq!(rect, Rect::new(100., 550., 300., 650.));
+
+        // The window reports the size of the applied rect back.
+        app.world_mut().get_mut::<Window>(popup).unwrap().resolution.set(rect.width(), rect.height());
+        app.update();
+        assert_eq!(backend.rects.lock().unwrap()[&popup], rect);
+        assert_eq!(app.world().get::<PlacedPopup>(popup), Some(&PlacedPopup(rect, Window::default().size())));
+    }
+}
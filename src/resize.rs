@@ -0,0 +1,334 @@
+use crate::backend::{ActiveBackend, SharedState};
+use crate::{ChildWindowLock, ChildWindowSystems};
+use bevy::app::{App, Plugin, PostUpdate};
+use bevy::math::{Rect, Vec2};
+use bevy::prelude::{Changed, Commands, Component, Entity, IntoScheduleConfigs, Or, Query, Reflect, ReflectComponent, ReflectDefault, ReflectDeserialize, ReflectSerialize, RemovedComponents, Res};
+use bevy::window::{SystemCursorIcon, Window};
+use bevy::winit::cursor::CursorIcon;
+use serde::{Deserialize, Serialize};
+
+pub(crate) struct ChildWindowResizePlugin;
+
+impl Plugin for ChildWindowResizePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<ChildWindowResizeBorder>()
+            .register_type::<ResizeEdges>()
+            .add_systems(PostUpdate, (
+                sync_resize_borders.in_set(ChildWindowSystems::Sync),
+                update_resize_cursors,
+            ));
+    }
+}
+
+/// Allows the user to resize the undecorated child window by dragging its edges.
+///
+/// When the cursor is within [`ChildWindowResizeBorder::thickness`] from an enabled edge or corner,
+/// the resize cursor is shown and dragging resizes the window.
+/// The resize is clamped to the client area of the parent window and respects [`Window::resize_constraints`].
+/// The border is disabled while the size of the child window is locked by [`ChildWindowLock`].
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::window::PrimaryWindow;
+/// use bevy_child_window::prelude::*;
+///
+/// fn spawn_child_window(
+///     mut commands: Commands,
+///     parent: Query<Entity, With<PrimaryWindow>>,
+/// ){
+///     commands.spawn((
+///         ParentWindow(parent.single().expect("Parent not found")),
+///         ChildWindowResizeBorder::default(),
+///         Window {
+///             decorations: false,
+///             ..Default::default()
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub struct ChildWindowResizeBorder {
+    /// The thickness of the border in logical pixels.
+    pub thickness: f32,
+    /// The edges and corners that can be dragged.
+    pub edges: ResizeEdges,
+}
+
+impl Default for ChildWindowResizeBorder {
+    fn default() -> Self {
+        Self {
+            thickness: 5.,
+            edges: ResizeEdges::ALL,
+        }
+    }
+}
+
+impl ChildWindowResizeBorder {
+    /// Returns the resize direction at `position` in logical pixels relative to the top-left corner of the client area,
+    /// or `None` if `position` is not on the border.
+    pub(crate) fn hit_test(&self, position: Vec2, size: Vec2) -> Option<ResizeDirection> {
+        if !Rect::from_corners(Vec2::ZERO, size).contains(position) {
+            return None;
+        }
+        let left = position.x <= self.thickness;
+        let right = size.x - self.thickness <= position.x;
+        let top = position.y <= self.thickness;
+        let bottom = size.y - self.thickness <= position.y;
+        let edges = &self.edges;
+        let dir = match (left, right, top, bottom) {
+            (true, _, true, _) if edges.top_left => ResizeDirection::new(true, false, true, false),
+            (_, true, true, _) if edges.top_right => ResizeDirection::new(false, true, true, false),
+            (true, _, _, true) if edges.bottom_left => ResizeDirection::new(true, false, false, true),
+            (_, true, _, true) if edges.bottom_right => ResizeDirection::new(false, true, false, true),
+            (true, ..) if edges.left => ResizeDirection::new(true, false, false, false),
+            (_, true, ..) if edges.right => ResizeDirection::new(false, true, false, false),
+            (_, _, true, _) if edges.top => ResizeDirection::new(false, false, true, false),
+            (.., true) if edges.bottom => ResizeDirection::new(false, false, false, true),
+            _ => return None,
+        };
+        Some(dir)
+    }
+}
+
+/// The edges and corners of [`ChildWindowResizeBorder`].
+#[derive(Reflect, Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[reflect(Serialize, Deserialize)]
+pub struct ResizeEdges {
+    /// The left edge.
+    pub left: bool,
+    /// The right edge.
+    pub right: bool,
+    /// The top edge.
+    pub top: bool,
+    /// The bottom edge.
+    pub bottom: bool,
+    /// The top-left corner.
+    pub top_left: bool,
+    /// The top-right corner.
+    pub top_right: bool,
+    /// The bottom-left corner.
+    pub bottom_left: bool,
+    /// The bottom-right corner.
+    pub bottom_right: bool,
+}
+
+impl ResizeEdges {
+    /// All edges and corners.
+    pub const ALL: Self = Self {
+        left: true,
+        right: true,
+        top: true,
+        bottom: true,
+        top_left: true,
+        top_right: true,
+        bottom_left: true,
+        bottom_right: true,
+    };
+
+    /// Only the bottom-right corner.
+    pub const BOTTOM_RIGHT: Self = Self {
+        left: false,
+        right: false,
+        top: false,
+        bottom: false,
+        top_left: false,
+        top_right: false,
+        bottom_left: false,
+        bottom_right: true,
+    };
+}
+
+impl Default for ResizeEdges {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// The edges being dragged while resizing.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) struct ResizeDirection {
+    pub left: bool,
+    pub right: bool,
+    pub top: bool,
+    pub bottom: bool,
+}
+
+impl ResizeDirection {
+    pub const fn new(left: bool, right: bool, top: bool, bottom: bool) -> Self {
+        Self {
+            left,
+            right,
+            top,
+            bottom,
+        }
+    }
+
+    fn cursor_icon(&self) -> SystemCursorIcon {
+        match (self.left, self.right, self.top, self.bottom) {
+            (true, _, true, _) | (_, true, _, true) => SystemCursorIcon::NwseResize,
+            (true, _, _, true) | (_, true, true, _) => SystemCursorIcon::NeswResize,
+            (true, ..) | (_, true, ..) => SystemCursorIcon::EwResize,
+            _ => SystemCursorIcon::NsResize,
+        }
+    }
+}
+
+/// The resize border of a child window shared with the native event handlers.
+pub(crate) struct ResizeBorderHitTest {
+    border: ChildWindowResizeBorder,
+    scale_factor: f32,
+    min_size: Vec2,
+}
+
+impl SharedState {
+    /// Returns the resize direction at `position` in logical pixels relative to the client area of the child window of `size`.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub(crate) fn hit_test_resize_border(&self, entity: Entity, position: Vec2, size: Vec2) -> Option<ResizeDirection> {
+        self.resize_borders.lock().unwrap().get(&entity)?.border.hit_test(position, size)
+    }
+
+    /// Same as [`SharedState::hit_test_resize_border`], but `position` and `size` are in physical pixels.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub(crate) fn hit_test_resize_border_physical(&self, entity: Entity, position: Vec2, size: Vec2) -> Option<ResizeDirection> {
+        let borders = self.resize_borders.lock().unwrap();
+        let hit_test = borders.get(&entity)?;
+        hit_test.border.hit_test(position / hit_test.scale_factor, size / hit_test.scale_factor)
+    }
+
+    /// Returns the minimum size of the child window in logical pixels.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub(crate) fn resize_min_size(&self, entity: Entity) -> Vec2 {
+        self.resize_borders
+            .lock()
+            .unwrap()
+            .get(&entity)
+            .map_or(Vec2::ZERO, |hit_test| hit_test.min_size)
+    }
+}
+
+fn sync_resize_borders(
+    mut removed: RemovedComponents<ChildWindowResizeBorder>,
+    borders: Query<(Entity, &ChildWindowResizeBorder, &Window), Or<(Changed<ChildWindowResizeBorder>, Changed<Window>)>>,
+    backend: Res<ActiveBackend>,
+) {
+    let mut hit_tests = backend.shared.resize_borders.lock().unwrap();
+    for entity in removed.read() {
+        hit_tests.remove(&entity);
+    }
+    for (entity, border, window) in borders.iter() {
+        let constraints = window.resize_constraints.check_constraints();
+        hit_tests.insert(entity, ResizeBorderHitTest {
+            border: *border,
+            scale_factor: window.scale_factor(),
+            min_size: Vec2::new(constraints.min_width, constraints.min_height),
+        });
+    }
+}
+
+fn update_resize_cursors(
+    mut commands: Commands,
+    windows: Query<
+        (Entity, &Window, &ChildWindowResizeBorder, Option<&CursorIcon>, Option<&ChildWindowLock>),
+        Or<(Changed<Window>, Changed<ChildWindowLock>)>,
+    >,
+) {
+    for (entity, window, border, cursor_icon, lock) in windows.iter() {
+        let icon = window
+            .cursor_position()
+            .filter(|_| !lock.is_some_and(|lock| lock.size))
+            .and_then(|position| border.hit_test(position, window.size()))
+            .map(|dir| CursorIcon::System(dir.cursor_icon()));
+        match (icon, cursor_icon) {
+            (Some(icon), current) if current != Some(&icon) => {
+                commands.entity(entity).insert(icon);
+            }
+            (None, Some(CursorIcon::System(current))) if is_resize_cursor(*current) => {
+                commands.entity(entity).insert(CursorIcon::default());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_resize_cursor(icon: SystemCursorIcon) -> bool {
+    matches!(icon, SystemCursorIcon::NwseResize | SystemCursorIcon::NeswResize | SystemCursorIcon::EwResize | SystemCursorIcon::NsResize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChildWindowSettings;
+
+    const SIZE: Vec2 = Vec2::new(400., 300.);
+
+    fn border(edges: ResizeEdges) -> ChildWindowResizeBorder {
+        ChildWindowResizeBorder {
+            thickness: 5.,
+            edges,
+        }
+    }
+
+    #[test]
+    fn corner_takes_precedence_over_edges() {
+        let border = border(ResizeEdges::ALL);
+        assert_eq!(border.hit_test(Vec2::new(2., 2.), SIZE), Some(ResizeDirection::new(true, false, true, false)));
+        assert_eq!(border.hit_test(Vec2::new(398., 2.), SIZE), Some(ResizeDirection::new(false, true, true, false)));
+        assert_eq!(border.hit_test(Vec2::new(2., 298.), SIZE), Some(ResizeDirection::new(true, false, false, true)));
+        assert_eq!(border.hit_test(Vec2::new(398., 298.), SIZE), Some(ResizeDirection::new(false, true, false, true)));
+    }
+
+    #[test]
+    fn edges_outside_corners() {
+        let border = border(ResizeEdges::ALL);
+        assert_eq!(border.hit_test(Vec2::new(2., 150.), SIZE), Some(ResizeDirection::new(true, false, false, false)));
+        assert_eq!(border.hit_test(Vec2::new(398., 150.), SIZE), Some(ResizeDirection::new(false, true, false, false)));
+        assert_eq!(border.hit_test(Vec2::new(200., 2.), SIZE), Some(ResizeDirection::new(false, false, true, false)));
+        assert_eq!(border.hit_test(Vec2::new(200., 298.), SIZE), Some(ResizeDirection::new(false, false, false, true)));
+        assert_eq!(border.hit_test(Vec2::new(200., 150.), SIZE), None);
+        assert_eq!(border.hit_test(Vec2::new(-2., 150.), SIZE), None);
+    }
+
+    #[test]
+    fn disabled_corner_falls_back_to_edge() {
+        let border = border(ResizeEdges {
+            top_left: false,
+            ..ResizeEdges::ALL
+        });
+        assert_eq!(border.hit_test(Vec2::new(2., 2.), SIZE), Some(ResizeDirection::new(true, false, false, false)));
+
+        let border = ChildWindowResizeBorder {
+            edges: ResizeEdges {
+                top_left: false,
+                left: false,
+                ..ResizeEdges::ALL
+            },
+            ..border
+        };
+        assert_eq!(border.hit_test(Vec2::new(2., 2.), SIZE), Some(ResizeDirection::new(false, false, true, false)));
+    }
+
+    #[test]
+    fn disabled_edges_are_not_hit() {
+        let border = border(ResizeEdges::BOTTOM_RIGHT);
+        assert_eq!(border.hit_test(Vec2::new(398., 298.), SIZE), Some(ResizeDirection::new(false, true, false, true)));
+        assert_eq!(border.hit_test(Vec2::new(2., 2.), SIZE), None);
+        assert_eq!(border.hit_test(Vec2::new(398., 150.), SIZE), None);
+        assert_eq!(border.hit_test(Vec2::new(200., 298.), SIZE), None);
+    }
+
+    #[test]
+    fn physical_position_is_scaled() {
+        let shared = SharedState::new(ChildWindowSettings::default());
+        let entity = Entity::from_raw(1);
+        shared.resize_borders.lock().unwrap().insert(entity, ResizeBorderHitTest {
+            border: border(ResizeEdges::ALL),
+            scale_factor: 2.,
+            min_size: Vec2::ZERO,
+        });
+        assert_eq!(shared.hit_test_resize_border_physical(entity, Vec2::new(8., 300.), SIZE * 2.), Some(ResizeDirection::new(true, false, false, false)));
+        assert_eq!(shared.hit_test_resize_border_physical(entity, Vec2::new(12., 300.), SIZE * 2.), None);
+    }
+}
@@ -0,0 +1,45 @@
+use bevy::prelude::SystemSet;
+
+/// The system sets of the child window systems.
+///
+/// Use these to order your own systems relative to the child windows, e.g. after they are attached.
+///
+/// [`ChildWindowSystems::Attach`] runs in [`ChildWindowPlugin::attach_schedule`](crate::ChildWindowPlugin::attach_schedule).
+/// [`ChildWindowSystems::Interaction`] runs in `PreUpdate`.
+/// [`ChildWindowSystems::Sync`] and [`ChildWindowSystems::Layout`] run in `PostUpdate` in this order,
+/// after [`ChildWindowSystems::Attach`] if it also runs in `PostUpdate`.
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_child_window::prelude::*;
+///
+/// fn main() {
+///     App::new()
+///         .add_plugins((
+///             DefaultPlugins,
+///             ChildWindowPlugin::default(),
+///         ))
+///         .add_systems(Update, on_attached.after(ChildWindowSystems::Attach))
+///         .run();
+/// }
+///
+/// fn on_attached() {}
+/// ```
+#[derive(SystemSet, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ChildWindowSystems {
+    /// Converts the windows with [`ParentWindow`](crate::ParentWindow) into native child windows,
+    /// and detaches the ones whose [`ParentWindow`](crate::ParentWindow) was removed.
+    ///
+    /// The native window is created by `bevy_winit` after the frame in which [`Window`](bevy::window::Window) is spawned,
+    /// so a child window is attached in the next frame at the earliest.
+    Attach,
+    /// Writes the events of the interactions reported by the native windows, such as [`ChildWindowDragged`](crate::ChildWindowDragged).
+    ///
+    /// This runs in `PreUpdate`.
+    Interaction,
+    /// Mirrors the components of the child windows to the native windows, such as [`ChildWindowLock`](crate::ChildWindowLock).
+    Sync,
+    /// Moves and resizes the child windows placed by the plugin, such as [`ChildWindowPopup`](crate::ChildWindowPopup).
+    Layout,
+}
@@ -0,0 +1,285 @@
+use crate::backend::{ActiveBackend, SharedState};
+use crate::{ChildWindowError, ChildWindowSystems, ParentWindow, UnInitializeWindow};
+use crate::util::log_error;
+use bevy::app::{App, Plugin, PostUpdate};
+use bevy::ecs::schedule::InternedScheduleLabel;
+use bevy::prelude::{Changed, Commands, Component, DetectChanges, Entity, EventWriter, IntoScheduleConfigs, NonSend, Or, Query, Reflect, ReflectComponent, ReflectDefault, ReflectDeserialize, ReflectResource, ReflectSerialize, RemovedComponents, Res, Resource, With, World};
+use bevy::winit::WinitWindows;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub(crate) struct ChildWindowSettingsPlugin {
+    pub(crate) settings: ChildWindowSettings,
+    pub(crate) attach_schedule: InternedScheduleLabel,
+}
+
+impl Plugin for ChildWindowSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<ChildWindowSettings>()
+            .register_type::<ChildWindowSettingsOverride>()
+            .insert_resource(self.settings)
+            .add_systems(self.attach_schedule, give_up_attaching.after(ChildWindowSystems::Attach))
+            .add_systems(PostUpdate, sync_window_settings.in_set(ChildWindowSystems::Sync));
+    }
+}
+
+/// The default behaviors of child windows.
+///
+/// The initial value is taken from [`ChildWindowPlugin::settings`](crate::ChildWindowPlugin::settings),
+/// and it can also be changed at runtime.
+/// Each child window can override them with [`ChildWindowSettingsOverride`].
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_child_window::prelude::*;
+///
+/// fn main() {
+///     App::new()
+///         .add_plugins((
+///             DefaultPlugins,
+///             ChildWindowPlugin {
+///                 settings: ChildWindowSettings {
+///                     raise_on_click: false,
+///                     log_level: ChildWindowLogLevel::Error,
+///                     ..default()
+///                 },
+///                 ..default()
+///             },
+///         ))
+///         .run();
+/// }
+/// ```
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+#[reflect(Resource, Serialize, Deserialize, Default)]
+pub struct ChildWindowSettings {
+    /// Where the user can move and resize child windows.
+    pub containment: Containment,
+    /// Whether a child window is brought to the front of its siblings when clicked.
+    pub raise_on_click: bool,
+    /// What is treated as the title bar that starts moving a child window.
+    pub title_bar_height: TitleBarHeight,
+    /// How long attaching a child window to its parent window is retried.
+    pub attach_retry: AttachRetryPolicy,
+    /// The most verbose level of the logs of this crate.
+    pub log_level: ChildWindowLogLevel,
+}
+
+/// Overrides [`ChildWindowSettings`] for the child window.
+///
+/// The fields that are `None` fall back to [`ChildWindowSettings`].
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::window::PrimaryWindow;
+/// use bevy_child_window::prelude::*;
+///
+/// fn spawn_child_window(
+///     mut commands: Commands,
+///     parent: Query<Entity, With<PrimaryWindow>>,
+/// ){
+///     commands.spawn((
+///         ParentWindow(parent.single().expect("Parent not found")),
+///         // The header of 30px height drawn by the app works as the title bar.
+///         ChildWindowSettingsOverride {
+///             title_bar_height: Some(TitleBarHeight::Fixed(30.)),
+///             ..default()
+///         },
+///         Window {
+///             decorations: false,
+///             ..default()
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub struct ChildWindowSettingsOverride {
+    /// Overrides [`ChildWindowSettings::containment`].
+    pub containment: Option<Containment>,
+    /// Overrides [`ChildWindowSettings::raise_on_click`].
+    pub raise_on_click: Option<bool>,
+    /// Overrides [`ChildWindowSettings::title_bar_height`].
+    pub title_bar_height: Option<TitleBarHeight>,
+    /// Overrides [`ChildWindowSettings::attach_retry`].
+    pub attach_retry: Option<AttachRetryPolicy>,
+}
+
+/// Where the user can move and resize a child window.
+#[derive(Reflect, Serialize, Deserialize, Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Containment {
+    /// The child window is kept inside the client area of its parent window.
+    #[default]
+    ClientArea,
+    /// The child window can be moved and resized beyond its parent window.
+    Unconstrained,
+}
+
+/// What is treated as the title bar of a child window.
+#[derive(Reflect, Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq)]
+pub enum TitleBarHeight {
+    /// The title bar drawn by the OS.
+    #[default]
+    Native,
+    /// The top area of the client area of the given height in logical pixels, in addition to the title bar drawn by the OS.
+    ///
+    /// This is for undecorated child windows that draw their own title bar.
+    Fixed(f32),
+}
+
+/// How long attaching a child window to its parent window is retried, e.g. while the native window isn't ready.
+#[derive(Reflect, Serialize, Deserialize, Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum AttachRetryPolicy {
+    /// Retries every frame until it succeeds.
+    #[default]
+    Forever,
+    /// Gives up after the given number of frames in which both native windows exist.
+    ///
+    /// [`ParentWindow`] is then removed and [`ChildWindowError::AttachFailed`] is fired.
+    Frames(u32),
+}
+
+/// The verbosity of the logs of this crate.
+#[derive(Reflect, Serialize, Deserialize, Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum ChildWindowLogLevel {
+    /// Nothing is logged.
+    Off,
+    /// Only errors are logged.
+    Error,
+    /// Errors and warnings are logged.
+    #[default]
+    Warn,
+    /// Everything is logged, including the attachment and detachment of child windows.
+    Debug,
+}
+
+impl ChildWindowSettings {
+    /// Returns the settings of the child window with `settings_override` applied.
+    pub fn resolve(&self, settings_override: Option<&ChildWindowSettingsOverride>) -> Self {
+        let Some(o) = settings_override else {
+            return *self;
+        };
+        Self {
+            containment: o.containment.unwrap_or(self.containment),
+            raise_on_click: o.raise_on_click.unwrap_or(self.raise_on_click),
+            title_bar_height: o.title_bar_height.unwrap_or(self.title_bar_height),
+            attach_retry: o.attach_retry.unwrap_or(self.attach_retry),
+            log_level: self.log_level,
+        }
+    }
+}
+
+impl Default for ChildWindowSettings {
+    fn default() -> Self {
+        DEFAULT_SETTINGS
+    }
+}
+
+const DEFAULT_SETTINGS: ChildWindowSettings = ChildWindowSettings {
+    containment: Containment::ClientArea,
+    raise_on_click: true,
+    title_bar_height: TitleBarHeight::Native,
+    attach_retry: AttachRetryPolicy::Forever,
+    log_level: ChildWindowLogLevel::Warn,
+};
+
+/// The settings of child windows shared with the native event handlers.
+#[derive(Default)]
+pub(crate) struct SharedSettings {
+    default: ChildWindowSettings,
+    windows: BTreeMap<Entity, ChildWindowSettings>,
+}
+
+impl SharedSettings {
+    pub(crate) fn new(default: ChildWindowSettings) -> Self {
+        Self {
+            default,
+            windows: BTreeMap::new(),
+        }
+    }
+}
+
+impl SharedState {
+    /// Returns the settings of the child window resolved with its [`ChildWindowSettingsOverride`].
+    pub(crate) fn window_settings(&self, entity: Entity) -> ChildWindowSettings {
+        let settings = self.settings.lock().unwrap();
+        settings.windows.get(&entity).copied().unwrap_or(settings.default)
+    }
+
+    /// Returns [`ChildWindowSettings::log_level`].
+    pub(crate) fn log_level(&self) -> ChildWindowLogLevel {
+        self.settings.lock().unwrap().default.log_level
+    }
+}
+
+/// Returns [`ChildWindowSettings::log_level`], or the default one if the plugin isn't added.
+pub(crate) fn log_level(world: &World) -> ChildWindowLogLevel {
+    world
+        .get_resource::<ChildWindowSettings>()
+        .map(|settings| settings.log_level)
+        .unwrap_or_default()
+}
+
+fn sync_window_settings(
+    mut removed: RemovedComponents<ChildWindowSettingsOverride>,
+    settings: Res<ChildWindowSettings>,
+    changed: Query<(), Changed<ChildWindowSettingsOverride>>,
+    overrides: Query<(Entity, &ChildWindowSettingsOverride)>,
+    backend: Res<ActiveBackend>,
+) {
+    if !settings.is_changed() && changed.is_empty() && removed.is_empty() {
+        return;
+    }
+    removed.clear();
+    let mut shared = backend.shared.settings.lock().unwrap();
+    shared.default = *settings;
+    shared.windows = overrides
+        .iter()
+        .map(|(entity, settings_override)| (entity, settings.resolve(Some(settings_override))))
+        .collect();
+}
+
+/// The number of frames in which attaching the child window has failed.
+#[derive(Component, Debug, Default, Copy, Clone, Eq, PartialEq)]
+struct AttachAttempts(u32);
+
+fn give_up_attaching(
+    mut commands: Commands,
+    mut ew: EventWriter<ChildWindowError>,
+    settings: Res<ChildWindowSettings>,
+    windows: Query<
+        (Entity, &ParentWindow, Option<&ChildWindowSettingsOverride>, Option<&AttachAttempts>),
+        Or<(With<UnInitializeWindow>, With<AttachAttempts>)>,
+    >,
+    uninitialized: Query<(), With<UnInitializeWindow>>,
+    winit_windows: NonSend<WinitWindows>,
+) {
+    for (entity, ParentWindow(parent), settings_override, attempts) in windows.iter() {
+        if !uninitialized.contains(entity) {
+            commands.entity(entity).remove::<AttachAttempts>();
+            continue;
+        }
+        let AttachRetryPolicy::Frames(max_frames) = settings.resolve(settings_override).attach_retry else {
+            continue;
+        };
+        if winit_windows.get_window(entity).is_none() || winit_windows.get_window(*parent).is_none() {
+            continue;
+        }
+        let attempts = attempts.map_or(1, |AttachAttempts(n)| n + 1);
+        if attempts < max_frames {
+            commands.entity(entity).insert(AttachAttempts(attempts));
+            continue;
+        }
+        let e = ChildWindowError::AttachFailed {
+            window: entity,
+            parent: *parent,
+        };
+        log_error!(settings.log_level, "{e}");
+        ew.write(e);
+        commands
+            .entity(entity)
+            .remove::<(ParentWindow, UnInitializeWindow, AttachAttempts)>();
+    }
+}
@@ -0,0 +1,254 @@
+use crate::backend::ActiveBackend;
+use crate::{ChildWindowDragEnded, ChildWindowMinimized, ChildWindowSystems, ChildWindowResizeEnded, ParentWindow, UnInitializeWindow};
+use bevy::app::{App, Plugin, PostUpdate};
+use bevy::math::Rect;
+use bevy::prelude::{Commands, Component, DetectChangesMut, Entity, EventReader, Has, IntoScheduleConfigs, NonSend, Query, Reflect, ReflectComponent, ReflectDefault, ReflectDeserialize, ReflectSerialize, Res, Window, With, Without};
+use bevy::winit::WinitWindows;
+use serde::{Deserialize, Serialize};
+
+pub(crate) struct ChildWindowTabsPlugin;
+
+impl Plugin for ChildWindowTabsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<ChildWindowTabGroup>()
+            .register_type::<TabOrder>()
+            .register_type::<ActiveTab>()
+            .add_systems(PostUpdate, (
+                follow_moved_tabs,
+                apply_tab_groups,
+            )
+                .chain()
+                .in_set(ChildWindowSystems::Layout));
+    }
+}
+
+/// Groups child windows of the same parent window into tabs sharing one frame.
+///
+/// Spawn this as its own entity with [`TabOrder`] listing the child windows of the group.
+/// All the tabs are kept at [`ChildWindowTabGroup::rect`], and only the tab of [`ActiveTab`] is shown; the others are hidden natively.
+/// Tabs with [`ChildWindowMinimized`] stay hidden until they are restored.
+/// If the user moves or resizes the active tab, the rect of the group follows it.
+///
+/// The tab strip itself isn't drawn, so that apps can draw it with `bevy_ui` from [`TabOrder`] and [`ActiveTab`],
+/// and switch the tab by setting [`ActiveTab`].
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::window::PrimaryWindow;
+/// use bevy_child_window::prelude::*;
+///
+/// fn spawn_tab_group(
+///     mut commands: Commands,
+///     parent: Query<Entity, With<PrimaryWindow>>,
+/// ){
+///     let parent = parent.single().expect("Parent not found");
+///     let tabs = ["Scene", "Inspector"].map(|title| commands.spawn((
+///         ParentWindow(parent),
+///         Window {
+///             title: title.to_string(),
+///             ..default()
+///         },
+///     )).id());
+///     commands.spawn((
+///         ChildWindowTabGroup::new(Rect::new(0., 40., 600., 440.)),
+///         TabOrder(tabs.to_vec()),
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+#[require(TabOrder, ActiveTab)]
+pub struct ChildWindowTabGroup {
+    /// The rect of all the tabs in logical pixels relative to the top-left corner of the parent window's client area.
+    pub rect: Rect,
+}
+
+impl ChildWindowTabGroup {
+    /// Creates the tab group at `rect`.
+    pub const fn new(rect: Rect) -> Self {
+        Self { rect }
+    }
+}
+
+/// The child windows of [`ChildWindowTabGroup`] in the order of the tabs.
+///
+/// Child windows that are despawned are removed from the order.
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub struct TabOrder(pub Vec<Entity>);
+
+/// The child window shown in [`ChildWindowTabGroup`].
+///
+/// If `None` or not in [`TabOrder`], the first tab becomes active.
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub struct ActiveTab(pub Option<Entity>);
+
+/// The tab group the child window belongs to and the rect applied to it.
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+struct GroupedTab {
+    group: Entity,
+    rect: Rect,
+}
+
+fn follow_moved_tabs(
+    mut drag_ended: EventReader<ChildWindowDragEnded>,
+    mut resize_ended: EventReader<ChildWindowResizeEnded>,
+    mut groups: Query<&mut ChildWindowTabGroup>,
+    tabs: Query<&GroupedTab>,
+) {
+    let moved = drag_ended
+        .read()
+        .map(|e| (e.window, e.rect))
+        .chain(resize_ended.read().map(|e| (e.window, e.rect)))
+        .collect::<Vec<_>>();
+    // The rects of the events are the client area of the tab, which is what the rect of the group specifies.
+    for (window, rect) in moved {
+        let Ok(GroupedTab { group, .. }) = tabs.get(window) else {
+            continue;
+        };
+        if let Ok(mut tab_group) = groups.get_mut(*group) {
+            tab_group.set_if_neq(ChildWindowTabGroup::new(rect));
+        }
+    }
+}
+
+fn apply_tab_groups(
+    mut commands: Commands,
+    mut groups: Query<(Entity, &ChildWindowTabGroup, &mut TabOrder, &mut ActiveTab)>,
+    mut tabs: Query<(&ParentWindow, &mut Window, Option<&GroupedTab>, Has<ChildWindowMinimized>), Without<UnInitializeWindow>>,
+    all_tabs: Query<(Entity, &GroupedTab)>,
+    windows: Query<(), With<Window>>,
+    backend: Res<ActiveBackend>,
+    winit_windows: NonSend<WinitWindows>,
+) {
+    for (group, tab_group, mut order, mut active) in groups.iter_mut() {
+        if order.0.iter().any(|tab| !windows.contains(*tab)) {
+            order.0.retain(|tab| windows.contains(*tab));
+        }
+        let active_tab = active
+            .0
+            .filter(|tab| order.0.contains(tab))
+            .or_else(|| order.0.first().copied());
+        active.set_if_neq(ActiveTab(active_tab));
+
+        for tab in order.0.iter().copied() {
+            let Ok((ParentWindow(parent), mut window, grouped, minimized)) = tabs.get_mut(tab) else {
+                continue;
+            };
+            // Minimized tabs are kept hidden until they are restored.
+            let visible = active_tab == Some(tab);
+            if !minimized && window.visible != visible {
+                window.visible = visible;
+            }
+            let state = GroupedTab {
+                group,
+                rect: tab_group.rect,
+            };
+            if grouped != Some(&state) {
+                backend.set_rect(&winit_windows, tab, *parent, tab_group.rect);
+                commands.entity(tab).insert(state);
+            }
+        }
+    }
+
+    // Tabs removed from their group are shown again.
+    for (tab, GroupedTab { group, .. }) in all_tabs.iter() {
+        let in_group = groups
+            .get(*group)
+            .is_ok_and(|(_, _, order, _)| order.0.contains(&tab));
+        if in_group {
+            continue;
+        }
+        if let Ok((_, mut window, _, false)) = tabs.get_mut(tab) {
+            window.visible = true;
+        }
+        commands.entity(tab).remove::<GroupedTab>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::{mock_app, MockBackend};
+    use bevy::math::Vec2;
+    use std::sync::Arc;
+
+    const RECT: Rect = Rect {
+        min: Vec2::new(0., 40.),
+        max: Vec2::new(600., 440.),
+    };
+
+    fn spawn_tab_group(app: &mut App, tab_count: usize) -> (Entity, Vec<Entity>) {
+        let parent = app.world_mut().spawn(Window::default()).id();
+        let tabs = (0..tab_count)
+            .map(|_| app.world_mut().spawn((Window::default(), ParentWindow(parent))).id())
+            .collect::<Vec<_>>();
+        let group = app
+            .world_mut()
+            .spawn((ChildWindowTabGroup::new(RECT), TabOrder(tabs.clone())))
+            .id();
+        app.update();
+        (group, tabs)
+    }
+
+    fn visible(app: &App, tab: Entity) -> bool {
+        app.world().get::<Window>(tab).unwrap().visible
+    }
+
+    #[test]
+    fn first_tab_is_active_and_others_are_hidden() {
+        let backend = Arc::new(MockBackend::default());
+        let mut app = mock_app(backend.clone());
+        let (group, tabs) = spawn_tab_group(&mut app, 2);
+        assert_eq!(*app.world().get::<ActiveTab>(group).unwrap(), ActiveTab(Some(tabs[0])));
+        assert!(visible(&app, tabs[0]));
+        assert!(!visible(&app, tabs[1]));
+        for tab in tabs.iter() {
+            assert_eq!(backend.rects.lock().unwrap()[tab], RECT);
+        }
+
+        app.world_mut().entity_mut(group).insert(ActiveTab(Some(tabs[1])));
+        app.update();
+        assert!(!visible(&app, tabs[0]));
+        assert!(visible(&app, tabs[1]));
+    }
+
+    #[test]
+    fn despawned_tab_is_removed_from_order() {
+        let mut app = mock_app(Arc::new(MockBackend::default()));
+        let (group, tabs) = spawn_tab_group(&mut app, 2);
+        app.world_mut().despawn(tabs[0]);
+        app.update();
+        assert_eq!(app.world().get::<TabOrder>(group).unwrap().0, [tabs[1]]);
+        assert_eq!(*app.world().get::<ActiveTab>(group).unwrap(), ActiveTab(Some(tabs[1])));
+        assert!(visible(&app, tabs[1]));
+    }
+
+    #[test]
+    fn tab_removed_from_group_is_shown_again() {
+        let mut app = mock_app(Arc::new(MockBackend::default()));
+        let (group, tabs) = spawn_tab_group(&mut app, 2);
+        app.world_mut().entity_mut(group).insert(TabOrder(vec![tabs[0]]));
+        app.update();
+        assert!(visible(&app, tabs[1]));
+        assert!(!app.world().entity(tabs[1]).contains::<GroupedTab>());
+    }
+
+    #[test]
+    fn moving_active_tab_moves_group() {
+        let backend = Arc::new(MockBackend::default());
+        let mut app = mock_app(backend.clone());
+        let (group, tabs) = spawn_tab_group(&mut app, 2);
+        let moved = Rect::new(100., 140., 700., 540.);
+        app.world_mut().send_event(ChildWindowDragEnded {
+            window: tabs[0],
+            rect: moved,
+        });
+        app.update();
+        assert_eq!(app.world().get::<ChildWindowTabGroup>(group).unwrap().rect, moved);
+        assert_eq!(backend.rects.lock().unwrap()[&tabs[1]], moved);
+    }
+}
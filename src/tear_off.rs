@@ -0,0 +1,322 @@
+use crate::backend::{ActiveBackend, SharedState};
+use crate::util::log_debug;
+use crate::{ChildWindowSettings, ChildWindowSystems, ParentWindow, UnInitializeWindow};
+use bevy::app::{App, Plugin, PostUpdate, PreUpdate};
+use bevy::math::{IVec2, Rect, Vec2};
+use bevy::prelude::{Commands, Component, Entity, Event, EventWriter, IntoScheduleConfigs, NonSend, Query, Reflect, ReflectComponent, ReflectDefault, ReflectDeserialize, ReflectSerialize, Res, Window, With, Without};
+use bevy::window::WindowPosition;
+use bevy::winit::WinitWindows;
+use serde::{Deserialize, Serialize};
+
+pub(crate) struct ChildWindowTearOffPlugin;
+
+impl Plugin for ChildWindowTearOffPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<ChildWindowTearOff>()
+            .add_event::<ChildWindowTornOff>()
+            .add_event::<ChildWindowReembedded>()
+            .add_systems(PreUpdate, tear_off_child_windows.after(ChildWindowSystems::Interaction))
+            .add_systems(PostUpdate, (
+                reembed_torn_off_windows,
+                place_reembedded_windows,
+            )
+                .chain()
+                .in_set(ChildWindowSystems::Layout));
+    }
+}
+
+/// Allows the user to tear the child window off its parent window by dragging it outside.
+///
+/// While the child window is being moved, it is kept inside the parent window as usual,
+/// but once the cursor goes farther than [`ChildWindowTearOff::threshold`] outside the client area of the parent window,
+/// [`ParentWindow`] is removed and the child window becomes an independent top-level window.
+/// [`ChildWindowTornOff`] is fired at this time.
+///
+/// When the torn-off window is dragged back so that the center of the top edge of its client area, i.e. right below its title bar,
+/// comes more than the threshold inside the client area of the original parent window,
+/// it is embedded again and [`ChildWindowReembedded`] is fired.
+///
+/// The torn-off window must first leave the parent window before it can be embedded again,
+/// and the re-embedded window can't be torn off until it is attached and placed again,
+/// so the child window never switches back and forth within a frame.
+///
+/// # Example
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::window::PrimaryWindow;
+/// use bevy_child_window::prelude::*;
+///
+/// fn spawn_child_window(
+///     mut commands: Commands,
+///     parent: Query<Entity, With<PrimaryWindow>>,
+/// ){
+///     commands.spawn((
+///         ParentWindow(parent.single().expect("Parent not found")),
+///         ChildWindowTearOff::default(),
+///         Window::default(),
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub struct ChildWindowTearOff {
+    /// The distance in logical pixels the cursor must go outside the parent window to tear the child window off,
+    /// and the distance the torn-off window must come inside to be embedded again.
+    pub threshold: f32,
+}
+
+impl Default for ChildWindowTearOff {
+    fn default() -> Self {
+        Self {
+            threshold: 48.,
+        }
+    }
+}
+
+/// Fired when the child window is torn off its parent window and becomes a top-level window.
+#[derive(Event, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ChildWindowTornOff {
+    /// The entity of the torn-off window.
+    pub window: Entity,
+    /// The entity of the parent window it was torn off.
+    pub parent: Entity,
+}
+
+/// Fired when the torn-off window is embedded into its parent window again.
+#[derive(Event, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ChildWindowReembedded {
+    /// The entity of the child window.
+    pub window: Entity,
+    /// The entity of the parent window.
+    pub parent: Entity,
+}
+
+/// The distance of the cursor outside the parent window while the child window is moved by the user.
+pub(crate) struct DragOvershoot {
+    window: Entity,
+    distance: f32,
+    physical: bool,
+    /// The unclamped position of the child window in physical screen coordinates, if known.
+    position: Option<IVec2>,
+}
+
+impl SharedState {
+    /// Queues the distance of the cursor outside the parent window in logical pixels.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub(crate) fn push_drag_overshoot(&self, window: Entity, distance: f32) {
+        self.drag_overshoots.lock().unwrap().push(DragOvershoot {
+            window,
+            distance,
+            physical: false,
+            position: None,
+        });
+    }
+
+    /// Queues the distance of the cursor outside the parent window in physical pixels,
+    /// with the position the child window would have if it weren't kept inside the parent window.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub(crate) fn push_drag_overshoot_physical(&self, window: Entity, distance: f32, position: IVec2) {
+        self.drag_overshoots.lock().unwrap().push(DragOvershoot {
+            window,
+            distance,
+            physical: true,
+            position: Some(position),
+        });
+    }
+}
+
+/// Returns how far `point` is outside `area`, or zero if it is inside.
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+pub(crate) fn distance_outside(area: Rect, point: Vec2) -> f32 {
+    (area.min - point)
+        .max(point - area.max)
+        .max_element()
+        .max(0.)
+}
+
+/// The parent window the child window was torn off.
+#[derive(Component, Debug, Copy, Clone, Eq, PartialEq)]
+struct TornOff {
+    parent: Entity,
+    /// Becomes true once the torn-off window leaves the parent window, so that it isn't embedded again right after the tear-off.
+    left_parent: bool,
+}
+
+/// The rect to apply once the re-embedded child window is attached.
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+struct Reembedded(Rect);
+
+fn tear_off_child_windows(
+    mut commands: Commands,
+    mut torn_off: EventWriter<ChildWindowTornOff>,
+    mut windows: Query<(&ParentWindow, &ChildWindowTearOff, &mut Window), (Without<UnInitializeWindow>, Without<Reembedded>)>,
+    settings: Res<ChildWindowSettings>,
+    backend: Res<ActiveBackend>,
+) {
+    let overshoots = std::mem::take(&mut *backend.shared.drag_overshoots.lock().unwrap());
+    for DragOvershoot { window, distance, physical, position } in overshoots {
+        let Ok((ParentWindow(parent), tear_off, mut child)) = windows.get_mut(window) else {
+            continue;
+        };
+        let distance = if physical {
+            distance / child.scale_factor()
+        } else {
+            distance
+        };
+        if distance <= tear_off.threshold {
+            continue;
+        }
+        if let Some(position) = position {
+            child.position = WindowPosition::At(position);
+        }
+        let parent = *parent;
+        commands
+            .entity(window)
+            .remove::<ParentWindow>()
+            .insert(TornOff {
+                parent,
+                left_parent: false,
+            });
+        log_debug!(settings.log_level, "Tore the child window {window} off {parent}");
+        torn_off.write(ChildWindowTornOff { window, parent });
+    }
+}
+
+fn reembed_torn_off_windows(
+    mut commands: Commands,
+    mut reembedded: EventWriter<ChildWindowReembedded>,
+    mut windows: Query<(Entity, &mut TornOff, Option<&ChildWindowTearOff>), Without<ParentWindow>>,
+    parents: Query<(), With<Window>>,
+    settings: Res<ChildWindowSettings>,
+    winit_windows: NonSend<WinitWindows>,
+) {
+    for (entity, mut torn_off, tear_off) in windows.iter_mut() {
+        let Some(tear_off) = tear_off.filter(|_| parents.contains(torn_off.parent)) else {
+            commands.entity(entity).remove::<TornOff>();
+            continue;
+        };
+        let (Some(window), Some(parent)) = (winit_windows.get_window(entity), winit_windows.get_window(torn_off.parent)) else {
+            continue;
+        };
+        let (Ok(position), Ok(parent_position)) = (window.inner_position(), parent.inner_position()) else {
+            continue;
+        };
+        let scale_factor = parent.scale_factor() as f32;
+        let parent_position = Vec2::new(parent_position.x as f32, parent_position.y as f32) / scale_factor;
+        let parent_size = parent.inner_size().to_logical::<f32>(parent.scale_factor());
+        let parent_area = Rect::from_corners(parent_position, parent_position + Vec2::new(parent_size.width, parent_size.height));
+        let origin = Vec2::new(position.x as f32, position.y as f32) / scale_factor;
+        let size = window.inner_size().to_logical::<f32>(window.scale_factor());
+        let rect = Rect::from_corners(origin, origin + Vec2::new(size.width, size.height));
+        let Some(rect) = reembedded_rect(&mut torn_off, tear_off.threshold, parent_area, rect) else {
+            continue;
+        };
+        commands
+            .entity(entity)
+            .remove::<TornOff>()
+            .insert((ParentWindow(torn_off.parent), Reembedded(rect)));
+        log_debug!(settings.log_level, "Embedded the torn-off window {entity} into {} again", torn_off.parent);
+        reembedded.write(ChildWindowReembedded {
+            window: entity,
+            parent: torn_off.parent,
+        });
+    }
+}
+
+/// Returns the rect of the torn-off window relative to the parent window if it should be embedded again.
+///
+/// `parent_area` and `rect` are the client areas of the parent window and the torn-off window in logical screen coordinates.
+fn reembedded_rect(torn_off: &mut TornOff, threshold: f32, parent_area: Rect, rect: Rect) -> Option<Rect> {
+    let title_bar = Vec2::new(rect.center().x, rect.min.y);
+    if !parent_area.contains(title_bar) {
+        torn_off.left_parent = true;
+        return None;
+    }
+    if !torn_off.left_parent || !parent_area.inflate(-threshold).contains(title_bar) {
+        return None;
+    }
+    Some(Rect::from_corners(rect.min - parent_area.min, rect.max - parent_area.min))
+}
+
+/// The child window is positioned relative to the parent window after it is attached again.
+fn place_reembedded_windows(
+    mut commands: Commands,
+    windows: Query<(Entity, &ParentWindow, &Reembedded), Without<UnInitializeWindow>>,
+    backend: Res<ActiveBackend>,
+    winit_windows: NonSend<WinitWindows>,
+) {
+    for (entity, ParentWindow(parent), Reembedded(rect)) in windows.iter() {
+        backend.set_rect(&winit_windows, entity, *parent, *rect);
+        commands.entity(entity).remove::<Reembedded>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::{mock_app, MockBackend};
+    use bevy::prelude::Events;
+    use std::sync::Arc;
+
+    const PARENT_AREA: Rect = Rect {
+        min: Vec2::new(100., 100.),
+        max: Vec2::new(900., 700.),
+    };
+
+    fn rect_at(x: f32, y: f32) -> Rect {
+        Rect::from_corners(Vec2::new(x, y), Vec2::new(x + 200., y + 100.))
+    }
+
+    #[test]
+    fn torn_off_beyond_threshold() {
+        let mut app = mock_app(Arc::new(MockBackend::default()));
+        let parent = app.world_mut().spawn(Window::default()).id();
+        let child = app
+            .world_mut()
+            .spawn((Window::default(), ParentWindow(parent), ChildWindowTearOff::default()))
+            .id();
+        app.update();
+
+        app.world().resource::<ActiveBackend>().shared.push_drag_overshoot(child, 48.);
+        app.update();
+        assert!(app.world().entity(child).contains::<ParentWindow>());
+
+        app.world().resource::<ActiveBackend>().shared.push_drag_overshoot(child, 49.);
+        app.update();
+        assert!(!app.world().entity(child).contains::<ParentWindow>());
+        assert_eq!(*app.world().get::<TornOff>(child).unwrap(), TornOff {
+            parent,
+            left_parent: false,
+        });
+        let torn_off = app.world().resource::<Events<ChildWindowTornOff>>();
+        assert_eq!(torn_off.iter_current_update_events().copied().collect::<Vec<_>>(), [ChildWindowTornOff {
+            window: child,
+            parent,
+        }]);
+    }
+
+    #[test]
+    fn not_reembedded_before_leaving_parent() {
+        let mut torn_off = TornOff {
+            parent: Entity::PLACEHOLDER,
+            left_parent: false,
+        };
+        assert_eq!(reembedded_rect(&mut torn_off, 48., PARENT_AREA, rect_at(300., 300.)), None);
+        assert!(!torn_off.left_parent);
+
+        assert_eq!(reembedded_rect(&mut torn_off, 48., PARENT_AREA, rect_at(300., 750.)), None);
+        assert!(torn_off.left_parent);
+    }
+
+    #[test]
+    fn reembedded_beyond_threshold_inside_parent() {
+        let mut torn_off = TornOff {
+            parent: Entity::PLACEHOLDER,
+            left_parent: true,
+        };
+        // The center of the top edge is 40px inside the parent window.
+        assert_eq!(reembedded_rect(&mut torn_off, 48., PARENT_AREA, rect_at(300., 140.)), None);
+        assert_eq!(reembedded_rect(&mut torn_off, 48., PARENT_AREA, rect_at(300., 160.)), Some(Rect::new(200., 60., 400., 160.)));
+    }
+}
@@ -0,0 +1,143 @@
+use bevy::ecs::event::EventCursor;
+use bevy::prelude::{Event, Events};
+use bevy::reflect::{ReflectMut, Struct};
+
+/// Removes the new events that don't satisfy `f` before any system reads them.
+///
+/// The new events are the ones after `cursor`, which is then moved past the retained events.
+/// The older events stay buffered in order with their ids,
+/// so the readers that haven't run since they were sent, e.g. the ones in `FixedUpdate`
+/// or the ones in frames without an event update, still read them exactly once.
+///
+/// This must run before the readers of the new events, otherwise they may skip some of them.
+pub(crate) fn retain_events<E: Event>(
+    events: &mut Events<E>,
+    cursor: &mut EventCursor<E>,
+    f: impl Fn(&E) -> bool,
+)
+where
+    Events<E>: Struct,
+{
+    if !cursor.clone().read(events).all(&f) {
+        let oldest = events.oldest_event_count();
+        let previous = events.len() - events.iter_current_update_events().len();
+        let read = events.len() - cursor.len(events);
+        let mut drained = events.drain().enumerate();
+        let retain = |(i, event): (usize, E)| (i < read || f(&event)).then_some(event);
+        let previous_events = (&mut drained).take(previous).filter_map(retain).collect::<Vec<_>>();
+        let current_events = drained.filter_map(retain).collect::<Vec<_>>();
+        rewind_event_count(events, oldest);
+        events.send_batch(previous_events);
+        events.update();
+        events.send_batch(current_events);
+    }
+    *cursor = events.get_cursor_current();
+}
+
+/// Moves the count of the sent events back to `count`, so that the events sent next get the ids from `count` again.
+///
+/// [`Events`] has no public API for this, so its fields are set through reflection.
+fn rewind_event_count<E: Event>(events: &mut Events<E>, count: usize)
+where
+    Events<E>: Struct,
+{
+    for sequence in ["events_a", "events_b"] {
+        let start = events
+            .field_mut(sequence)
+            .and_then(|sequence| match sequence.reflect_mut() {
+                ReflectMut::Struct(sequence) => sequence.field_mut("start_event_count"),
+                _ => None,
+            })
+            .and_then(|start| start.try_downcast_mut::<usize>());
+        if let Some(start) = start {
+            *start = count;
+        }
+    }
+    let event_count = events
+        .field_mut("event_count")
+        .and_then(|event_count| event_count.try_downcast_mut::<usize>());
+    debug_assert!(event_count.is_some(), "The layout of Events has changed");
+    if let Some(event_count) = event_count {
+        *event_count = count;
+    }
+}
+
+/// Logs an error if the given [`ChildWindowSettings::log_level`](crate::ChildWindowSettings::log_level) allows it.
+macro_rules! log_error {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::ChildWindowLogLevel::Error <= $level {
+            bevy::log::error!($($arg)*);
+        }
+    };
+}
+
+/// Logs a warning if the given [`ChildWindowSettings::log_level`](crate::ChildWindowSettings::log_level) allows it.
+macro_rules! log_warn {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::ChildWindowLogLevel::Warn <= $level {
+            bevy::log::warn!($($arg)*);
+        }
+    };
+}
+
+/// Logs a debug message if the given [`ChildWindowSettings::log_level`](crate::ChildWindowSettings::log_level) allows it.
+macro_rules! log_debug {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::ChildWindowLogLevel::Debug <= $level {
+            bevy::log::debug!($($arg)*);
+        }
+    };
+}
+
+pub(crate) use {log_debug, log_error, log_warn};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::Reflect;
+
+    #[derive(Event, Reflect, Debug, Copy, Clone, Eq, PartialEq)]
+    struct TestEvent(u32);
+
+    #[test]
+    fn retained_events_are_read_once() {
+        let mut events = Events::<TestEvent>::default();
+        let mut cursor = EventCursor::default();
+        let mut reader = events.get_cursor();
+
+        events.send_batch([TestEvent(1), TestEvent(2), TestEvent(3)]);
+        events.update();
+        retain_events(&mut events, &mut cursor, |e| e.0 != 2);
+        assert_eq!(reader.read(&events).copied().collect::<Vec<_>>(), [TestEvent(1), TestEvent(3)]);
+
+        events.send_batch([TestEvent(4), TestEvent(5)]);
+        events.update();
+        retain_events(&mut events, &mut cursor, |e| e.0 != 4);
+        assert_eq!(reader.read(&events).copied().collect::<Vec<_>>(), [TestEvent(5)]);
+    }
+
+    #[test]
+    fn older_events_are_kept_for_readers_that_have_not_run() {
+        let mut events = Events::<TestEvent>::default();
+        let mut cursor = EventCursor::default();
+        let mut reader = events.get_cursor();
+        let mut late_reader = events.get_cursor();
+
+        events.send_batch([TestEvent(1), TestEvent(2), TestEvent(3)]);
+        events.update();
+        retain_events(&mut events, &mut cursor, |e| e.0 != 2);
+        assert_eq!(reader.read(&events).copied().collect::<Vec<_>>(), [TestEvent(1), TestEvent(3)]);
+
+        // The events aren't updated in this frame, so all the events are still in the same buffer.
+        events.send_batch([TestEvent(4), TestEvent(5)]);
+        retain_events(&mut events, &mut cursor, |e| e.0 != 4);
+        assert_eq!(reader.read(&events).copied().collect::<Vec<_>>(), [TestEvent(5)]);
+        assert_eq!(late_reader.read(&events).copied().collect::<Vec<_>>(), [TestEvent(1), TestEvent(3), TestEvent(5)]);
+
+        events.send_batch([TestEvent(6), TestEvent(7)]);
+        events.update();
+        retain_events(&mut events, &mut cursor, |e| e.0 != 7);
+        assert_eq!(reader.read(&events).copied().collect::<Vec<_>>(), [TestEvent(6)]);
+        assert_eq!(late_reader.read(&events).copied().collect::<Vec<_>>(), [TestEvent(6)]);
+    }
+}